@@ -0,0 +1,95 @@
+#include "app_config.h"
+#include <string.h>
+#include "nvs.h"
+#include "esp_log.h"
+#include "sdkconfig.h"
+
+static const char *TAG = "CONFIG";
+
+// NVS namespace and keys (keys are limited to 15 characters)
+#define CONFIG_NVS_NAMESPACE     "app_config"
+#define CONFIG_NVS_WIFI_SSID     "wifi_ssid"
+#define CONFIG_NVS_WIFI_PASSWORD "wifi_pass"
+#define CONFIG_NVS_MQTT_URL      "mqtt_url"
+#define CONFIG_NVS_MQTT_USERNAME "mqtt_user"
+#define CONFIG_NVS_MQTT_PASSWORD "mqtt_pass"
+#define CONFIG_NVS_MQTT_PREFIX   "mqtt_prefix"
+#define CONFIG_NVS_TANK_CAPACITY "tank_l"
+
+// Read a string from NVS, falling back to the Kconfig default
+static void load_string(nvs_handle_t nvs, bool nvs_open_ok, const char *key,
+                        char *out, size_t out_len, const char *fallback)
+{
+    size_t len = out_len;
+    if (nvs_open_ok && nvs_get_str(nvs, key, out, &len) == ESP_OK) {
+        ESP_LOGD(TAG, "%s loaded from NVS", key);
+        return;
+    }
+    strncpy(out, fallback, out_len - 1);
+    out[out_len - 1] = '\0';
+}
+
+void config_load(app_config_t *config)
+{
+    nvs_handle_t nvs;
+    bool nvs_open_ok = nvs_open(CONFIG_NVS_NAMESPACE, NVS_READONLY, &nvs) == ESP_OK;
+    if (!nvs_open_ok) {
+        ESP_LOGI(TAG, "No saved configuration, using defaults");
+    }
+    
+    memset(config, 0, sizeof(*config));
+    load_string(nvs, nvs_open_ok, CONFIG_NVS_WIFI_SSID, config->wifi_ssid,
+                sizeof(config->wifi_ssid), CONFIG_WIFI_SSID);
+    load_string(nvs, nvs_open_ok, CONFIG_NVS_WIFI_PASSWORD, config->wifi_password,
+                sizeof(config->wifi_password), CONFIG_WIFI_PASSWORD);
+    load_string(nvs, nvs_open_ok, CONFIG_NVS_MQTT_URL, config->mqtt_broker_url,
+                sizeof(config->mqtt_broker_url), CONFIG_MQTT_BROKER_URL);
+    load_string(nvs, nvs_open_ok, CONFIG_NVS_MQTT_USERNAME, config->mqtt_username,
+                sizeof(config->mqtt_username), CONFIG_MQTT_USERNAME);
+    load_string(nvs, nvs_open_ok, CONFIG_NVS_MQTT_PASSWORD, config->mqtt_password,
+                sizeof(config->mqtt_password), CONFIG_MQTT_PASSWORD);
+    load_string(nvs, nvs_open_ok, CONFIG_NVS_MQTT_PREFIX, config->mqtt_topic_prefix,
+                sizeof(config->mqtt_topic_prefix), CONFIG_MQTT_TOPIC_PREFIX);
+    
+    // Topics are built as "<prefix>/<topic>"
+    size_t prefix_len = strlen(config->mqtt_topic_prefix);
+    while (prefix_len > 0 && config->mqtt_topic_prefix[prefix_len - 1] == '/') {
+        config->mqtt_topic_prefix[--prefix_len] = '\0';
+    }
+    
+    config->tank_capacity_l = CONFIG_WATER_TANK_CAPACITY_L;
+    if (nvs_open_ok) {
+        nvs_get_u16(nvs, CONFIG_NVS_TANK_CAPACITY, &config->tank_capacity_l);
+    }
+    
+    if (nvs_open_ok) {
+        nvs_close(nvs);
+    }
+}
+
+esp_err_t config_save(const app_config_t *config)
+{
+    nvs_handle_t nvs;
+    esp_err_t err = nvs_open(CONFIG_NVS_NAMESPACE, NVS_READWRITE, &nvs);
+    if (err != ESP_OK) {
+        ESP_LOGE(TAG, "Failed to open NVS: %s", esp_err_to_name(err));
+        return err;
+    }
+    
+    err = nvs_set_str(nvs, CONFIG_NVS_WIFI_SSID, config->wifi_ssid);
+    if (err == ESP_OK) err = nvs_set_str(nvs, CONFIG_NVS_WIFI_PASSWORD, config->wifi_password);
+    if (err == ESP_OK) err = nvs_set_str(nvs, CONFIG_NVS_MQTT_URL, config->mqtt_broker_url);
+    if (err == ESP_OK) err = nvs_set_str(nvs, CONFIG_NVS_MQTT_USERNAME, config->mqtt_username);
+    if (err == ESP_OK) err = nvs_set_str(nvs, CONFIG_NVS_MQTT_PASSWORD, config->mqtt_password);
+    if (err == ESP_OK) err = nvs_set_str(nvs, CONFIG_NVS_MQTT_PREFIX, config->mqtt_topic_prefix);
+    if (err == ESP_OK) err = nvs_set_u16(nvs, CONFIG_NVS_TANK_CAPACITY, config->tank_capacity_l);
+    if (err == ESP_OK) err = nvs_commit(nvs);
+    nvs_close(nvs);
+    
+    if (err != ESP_OK) {
+        ESP_LOGE(TAG, "Failed to save configuration: %s", esp_err_to_name(err));
+    } else {
+        ESP_LOGI(TAG, "Configuration saved");
+    }
+    return err;
+}
@@ -0,0 +1,33 @@
+#ifndef APP_CONFIG_H
+#define APP_CONFIG_H
+
+#include <stdint.h>
+#include "esp_err.h"
+
+// Field sizes include the terminating NUL (SSID/password limits match wifi_config_t)
+#define APP_CONFIG_SSID_LEN      33
+#define APP_CONFIG_PASSWORD_LEN  65
+#define APP_CONFIG_URL_LEN       128
+#define APP_CONFIG_CRED_LEN      64
+#define APP_CONFIG_PREFIX_LEN    64
+
+// Device settings that can change in the field without reflashing
+typedef struct {
+    char wifi_ssid[APP_CONFIG_SSID_LEN];
+    char wifi_password[APP_CONFIG_PASSWORD_LEN];
+    char mqtt_broker_url[APP_CONFIG_URL_LEN];
+    char mqtt_username[APP_CONFIG_CRED_LEN];
+    char mqtt_password[APP_CONFIG_CRED_LEN];
+    char mqtt_topic_prefix[APP_CONFIG_PREFIX_LEN];  // No trailing slash
+    uint16_t tank_capacity_l;   // Water tank size in liters; 0 shows percent only
+} app_config_t;
+
+// Load settings: each field comes from NVS if saved, otherwise from its
+// Kconfig value (CONFIG_WIFI_SSID, CONFIG_MQTT_BROKER_URL, ...).
+// NVS must already be initialized.
+void config_load(app_config_t *config);
+
+// Persist all fields to NVS; they take precedence over Kconfig from the next load
+esp_err_t config_save(const app_config_t *config);
+
+#endif // APP_CONFIG_H
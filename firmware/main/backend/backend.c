@@ -9,30 +9,274 @@
 #include "backend.h"
 #include <stdio.h>
 #include <string.h>
+#include "nvs.h"
+#include "esp_log.h"
+#include "freertos/FreeRTOS.h"
+#include "freertos/semphr.h"
+#include "esp_err.h"
+#include "esp_timer.h"
+
+static const char *TAG = "BACKEND";
+
+// NVS namespace and keys for persisted state
+#define BACKEND_NVS_NAMESPACE "backend"
+#define BACKEND_NVS_BRIGHT    "bright"
+#define BACKEND_NVS_RELAX     "relax"
+#define BACKEND_NVS_WATER     "water"
+#define BACKEND_NVS_LEVEL     "brightness"
+
+// Water samples arrive every few seconds, so a new level is written to NVS at
+// most once per this delay (the latest level when it expires wins)
+#define BACKEND_WATER_SAVE_DELAY_US (60LL * 1000 * 1000)
+
+// Values last committed to NVS; a key is only rewritten when its value differs.
+// BACKEND_NVS_UNSAVED marks a key not known to be stored yet
+#define BACKEND_NVS_UNSAVED 0xFF
+static uint8_t saved_bright = BACKEND_NVS_UNSAVED;
+static uint8_t saved_relax = BACKEND_NVS_UNSAVED;
+static uint8_t saved_water = BACKEND_NVS_UNSAVED;
+static uint8_t saved_brightness = BACKEND_NVS_UNSAVED;
+static SemaphoreHandle_t save_lock = NULL;
+static esp_timer_handle_t water_save_timer = NULL;
 
 // Static state storage - using simple static variables
 // For thread safety in embedded systems, we can use critical sections if needed
 static volatile uint8_t bright_state = 0;
 static volatile uint8_t relax_state = 0;
 static volatile uint8_t water_level = 50; // Default 50%
+static volatile uint8_t brightness = 100; // Kept while lights are off
+static volatile uint16_t tank_capacity_l = 0; // 0 = unknown, percent only
+static volatile bool initialized = false;
+
+// Runtime log level, see backend_set_log_level()
+static int log_level = BACKEND_LOG_INFO;
+
+// Bright and relax are only written together under this lock (see lights_transition)
+static portMUX_TYPE lights_lock = portMUX_INITIALIZER_UNLOCKED;
+
+// Registered state change listeners
+static struct {
+    backend_listener_t listener;
+    void* user_data;
+} listeners[BACKEND_MAX_LISTENERS];
+static uint8_t listener_count = 0;
+
+// Edge-triggered water alert state
+static backend_water_alert_cb_t water_alert_cb = NULL;
+static water_band_t water_band = WATER_BAND_NORMAL;
+
+// Moving average over the last water_avg_window samples
+static uint8_t water_samples[BACKEND_WATER_AVG_MAX_WINDOW];
+static uint8_t water_sample_count = 0;
+static uint8_t water_sample_next = 0;
+static uint8_t water_avg_window = BACKEND_WATER_AVG_DEFAULT_WINDOW;
+static bool ui_water_smoothing = false;
 
 // External C callbacks - these are implemented in the UI layer
 extern void ui_update_water_level_async(int level);
-extern void ui_set_bright_state(int state);
-extern void ui_set_relax_state(int state);
-extern void publish_light_state(const char* mode, int state);
+extern void ui_set_bright_state_async(int state);
+extern void ui_set_relax_state_async(int state);
+extern esp_err_t publish_light_state(const char* mode, int state, uint8_t level);
+extern esp_err_t publish_water_level(uint8_t level);
+
+/**
+ * @brief Notify all listeners of a state change
+ */
+static void backend_notify(backend_event_type_t type, uint8_t value)
+{
+    const backend_event_t event = { .type = type, .value = value };
+    for (uint8_t i = 0; i < listener_count; i++) {
+        listeners[i].listener(&event, listeners[i].user_data);
+    }
+}
+
+/**
+ * @brief Save the current state to NVS
+ *
+ * Only keys whose value differs from the last commit are written; when nothing
+ * changed, NVS is not opened at all. Called from the setters and the water save timer.
+ */
+static void backend_save_state(void)
+{
+    if (save_lock != NULL) {
+        xSemaphoreTake(save_lock, portMAX_DELAY);
+    }
+
+    const uint8_t bright = bright_state;
+    const uint8_t relax = relax_state;
+    const uint8_t water = water_level;
+    const uint8_t level = brightness;
+
+    if (bright != saved_bright || relax != saved_relax ||
+        water != saved_water || level != saved_brightness) {
+        nvs_handle_t nvs;
+        if (nvs_open(BACKEND_NVS_NAMESPACE, NVS_READWRITE, &nvs) != ESP_OK) {
+            ESP_LOGW(TAG, "Failed to open NVS for writing");
+        } else {
+            if (bright != saved_bright) nvs_set_u8(nvs, BACKEND_NVS_BRIGHT, bright);
+            if (relax != saved_relax) nvs_set_u8(nvs, BACKEND_NVS_RELAX, relax);
+            if (water != saved_water) nvs_set_u8(nvs, BACKEND_NVS_WATER, water);
+            if (level != saved_brightness) nvs_set_u8(nvs, BACKEND_NVS_LEVEL, level);
+            if (nvs_commit(nvs) == ESP_OK) {
+                saved_bright = bright;
+                saved_relax = relax;
+                saved_water = water;
+                saved_brightness = level;
+            } else {
+                ESP_LOGW(TAG, "Failed to commit state to NVS");
+            }
+            nvs_close(nvs);
+        }
+    }
+
+    if (save_lock != NULL) {
+        xSemaphoreGive(save_lock);
+    }
+}
+
+/**
+ * @brief Water save timer expired: persist the latest level
+ */
+static void water_save_timer_cb(void* arg)
+{
+    backend_save_state();
+}
+
+/**
+ * @brief Persist the water level after BACKEND_WATER_SAVE_DELAY_US
+ *
+ * Does nothing if the stored level is current or a save is already pending.
+ * Without the timer (creation failed) the level is saved right away.
+ */
+static void backend_schedule_water_save(void)
+{
+    if (water_level == saved_water) {
+        return;
+    }
+    if (water_save_timer == NULL) {
+        backend_save_state();
+        return;
+    }
+    if (!esp_timer_is_active(water_save_timer)) {
+        esp_timer_start_once(water_save_timer, BACKEND_WATER_SAVE_DELAY_US);
+    }
+}
+
+/**
+ * @brief Load saved state from NVS
+ *
+ * Keys that were never saved (first boot) keep their current defaults.
+ */
+static void backend_load_state(void)
+{
+    nvs_handle_t nvs;
+    if (nvs_open(BACKEND_NVS_NAMESPACE, NVS_READONLY, &nvs) != ESP_OK) {
+        ESP_LOGI(TAG, "No saved state, using defaults");
+        return;
+    }
+    uint8_t value;
+    if (nvs_get_u8(nvs, BACKEND_NVS_BRIGHT, &value) == ESP_OK) bright_state = saved_bright = value ? 1 : 0;
+    if (nvs_get_u8(nvs, BACKEND_NVS_RELAX, &value) == ESP_OK) relax_state = saved_relax = value ? 1 : 0;
+    if (nvs_get_u8(nvs, BACKEND_NVS_WATER, &value) == ESP_OK && value <= 100) water_level = saved_water = value;
+    water_band = backend_water_band_next(WATER_BAND_NORMAL, water_level);
+    if (nvs_get_u8(nvs, BACKEND_NVS_LEVEL, &value) == ESP_OK && value <= 100) brightness = saved_brightness = value;
+    nvs_close(nvs);
+
+    // Both lights on can't happen through the setters; prefer bright
+    if (bright_state != 0 && relax_state != 0) {
+        relax_state = 0;
+    }
+}
 
 /**
  * @brief Initialize the backend
  *
  * Must be called once before using any other backend functions.
+ * NVS must already be initialized; saved state is restored and pushed to the UI.
  */
 void backend_init(void)
 {
     bright_state = 0;
     relax_state = 0;
     water_level = 50;
-    printf("[Backend] Initialized\n");
+    brightness = 100;
+    water_band = WATER_BAND_NORMAL;
+    if (save_lock == NULL) {
+        save_lock = xSemaphoreCreateMutex();
+    }
+    if (water_save_timer == NULL) {
+        const esp_timer_create_args_t timer_args = {
+            .callback = water_save_timer_cb,
+            .name = "water_save",
+        };
+        if (esp_timer_create(&timer_args, &water_save_timer) != ESP_OK) {
+            ESP_LOGW(TAG, "Water save timer unavailable, saving every sample");
+            water_save_timer = NULL;
+        }
+    }
+    backend_load_state();
+
+    ui_set_bright_state_async(bright_state);
+    ui_set_relax_state_async(relax_state);
+    ui_update_water_level_async((int)water_level);
+    initialized = true;
+    ESP_LOGI(TAG, "Initialized (bright=%d relax=%d water=%d%%)",
+             bright_state, relax_state, water_level);
+}
+
+/**
+ * @brief Check whether backend_init() has run
+ *
+ * @return true once the backend is initialized
+ */
+bool backend_is_initialized(void)
+{
+    return initialized;
+}
+
+/**
+ * @brief Register a listener for backend state changes
+ *
+ * @param listener Callback invoked on every state change
+ * @param user_data Passed back to the listener unchanged
+ * @return true if registered, false if the listener table is full
+ */
+bool backend_subscribe(backend_listener_t listener, void* user_data)
+{
+    if (listener == NULL || listener_count >= BACKEND_MAX_LISTENERS) {
+        return false;
+    }
+    listeners[listener_count].listener = listener;
+    listeners[listener_count].user_data = user_data;
+    listener_count++;
+    return true;
+}
+
+/**
+ * @brief Store a new bright/relax pair in one step
+ *
+ * The final pair is computed and written under lights_lock, so a reader on
+ * another task never sees a half-finished transition (both on, or both off
+ * while switching from one light to the other). Listeners and the UI are
+ * notified afterwards, when the getters already return the final state.
+ *
+ * @param bright New bright state, or -1 to keep it
+ * @param relax New relax state, or -1 to keep it
+ * @param old_bright Receives the previous bright state
+ * @param old_relax Receives the previous relax state
+ */
+static void lights_transition(int bright, int relax, uint8_t* old_bright, uint8_t* old_relax)
+{
+    taskENTER_CRITICAL(&lights_lock);
+    *old_bright = bright_state;
+    *old_relax = relax_state;
+    if (bright >= 0) {
+        bright_state = bright ? 1 : 0;
+    }
+    if (relax >= 0) {
+        relax_state = relax ? 1 : 0;
+    }
+    taskEXIT_CRITICAL(&lights_lock);
 }
 
 /**
@@ -42,17 +286,23 @@ void backend_init(void)
  */
 void backend_set_bright(uint8_t state)
 {
-    bright_state = state;
-    printf("[Backend] Bright state set to: %d\n", state);
+    uint8_t old_bright, old_relax;
+    state = state ? 1 : 0;
 
-    // If bright is on, turn off relax (mutual exclusion)
-    if (state != 0) {
-        relax_state = 0;
-        ui_set_relax_state(0);
+    // Turning bright on turns relax off in the same step (mutual exclusion)
+    lights_transition(state, state ? 0 : -1, &old_bright, &old_relax);
+    ESP_LOGD(TAG, "Bright state set to: %d", state);
+
+    if (state != 0 && old_relax != 0) {
+        ui_set_relax_state_async(0);
+        backend_notify(BACKEND_EVENT_RELAX_CHANGED, 0);
     }
 
+    backend_save_state();
+    backend_notify(BACKEND_EVENT_BRIGHT_CHANGED, state);
+
     // Publish to MQTT
-    publish_light_state("bright", state);
+    publish_light_state("bright", state, brightness);
 }
 
 /**
@@ -62,17 +312,138 @@ void backend_set_bright(uint8_t state)
  */
 void backend_set_relax(uint8_t state)
 {
-    relax_state = state;
-    printf("[Backend] Relax state set to: %d\n", state);
+    uint8_t old_bright, old_relax;
+    state = state ? 1 : 0;
 
-    // If relax is on, turn off bright (mutual exclusion)
-    if (state != 0) {
-        bright_state = 0;
-        ui_set_bright_state(0);
+    // Turning relax on turns bright off in the same step (mutual exclusion)
+    lights_transition(state ? 0 : -1, state, &old_bright, &old_relax);
+    ESP_LOGD(TAG, "Relax state set to: %d", state);
+
+    if (state != 0 && old_bright != 0) {
+        ui_set_bright_state_async(0);
+        backend_notify(BACKEND_EVENT_BRIGHT_CHANGED, 0);
     }
 
+    backend_save_state();
+    backend_notify(BACKEND_EVENT_RELAX_CHANGED, state);
+
     // Publish to MQTT
-    publish_light_state("relax", state);
+    publish_light_state("relax", state, brightness);
+}
+
+/**
+ * @brief Set the light mode
+ *
+ * @param mode Light mode to apply
+ */
+void backend_set_light_mode(light_mode_t mode)
+{
+    uint8_t old_bright, old_relax;
+
+    switch (mode) {
+        case LIGHT_MODE_BRIGHT:
+            backend_set_bright(1);
+            break;
+        case LIGHT_MODE_RELAX:
+            backend_set_relax(1);
+            break;
+        case LIGHT_MODE_OFF:
+        default:
+            lights_transition(0, 0, &old_bright, &old_relax);
+            if (old_bright == 0 && old_relax == 0) {
+                break;
+            }
+            ESP_LOGD(TAG, "Lights off");
+            backend_save_state();
+            if (old_bright != 0) {
+                backend_notify(BACKEND_EVENT_BRIGHT_CHANGED, 0);
+                publish_light_state("bright", 0, brightness);
+            }
+            if (old_relax != 0) {
+                backend_notify(BACKEND_EVENT_RELAX_CHANGED, 0);
+                publish_light_state("relax", 0, brightness);
+            }
+            break;
+    }
+}
+
+/**
+ * @brief Get the current light mode
+ *
+ * @return Light mode derived from the bright and relax states
+ */
+light_mode_t backend_get_light_mode(void)
+{
+    // Read both under the lock so a transition on the other core can't be seen half-done
+    taskENTER_CRITICAL(&lights_lock);
+    uint8_t bright = bright_state;
+    uint8_t relax = relax_state;
+    taskEXIT_CRITICAL(&lights_lock);
+
+    if (bright != 0) {
+        return LIGHT_MODE_BRIGHT;
+    }
+    if (relax != 0) {
+        return LIGHT_MODE_RELAX;
+    }
+    return LIGHT_MODE_OFF;
+}
+
+/**
+ * @brief Get the current light mode as a display string
+ *
+ * @param buf Receives "OFF", "BRIGHT" or "RELAX"
+ * @param len Size of buf
+ * @return Characters written without the NUL, or -1 on error
+ */
+int backend_get_light_mode_str(char* buf, size_t len)
+{
+    static const char* const names[] = {
+        [LIGHT_MODE_OFF] = "OFF",
+        [LIGHT_MODE_BRIGHT] = "BRIGHT",
+        [LIGHT_MODE_RELAX] = "RELAX",
+    };
+    if (buf == NULL) {
+        return -1;
+    }
+    const char* name = names[backend_get_light_mode()];
+    size_t name_len = strlen(name);
+    if (len <= name_len) {
+        return -1;
+    }
+    memcpy(buf, name, name_len + 1);
+    return (int)name_len;
+}
+
+/**
+ * @brief Apply a light command received over MQTT
+ *
+ * @param mode "bright" or "relax"
+ * @param state 0 for off, 1 for on
+ * @return true if the mode was recognised and applied
+ */
+bool backend_apply_light_command(const char* mode, uint8_t state)
+{
+    if (strcmp(mode, "bright") == 0) {
+        ui_set_bright_state_async(state);
+        backend_set_bright(state);
+    } else if (strcmp(mode, "relax") == 0) {
+        ui_set_relax_state_async(state);
+        backend_set_relax(state);
+    } else {
+        return false;
+    }
+    return true;
+}
+
+/**
+ * @brief Turn both lights off and clear both switches on screen
+ */
+void backend_lights_all_off(void)
+{
+    ui_set_bright_state_async(0);
+    ui_set_relax_state_async(0);
+    backend_set_light_mode(LIGHT_MODE_OFF);
 }
 
 /**
@@ -93,6 +464,40 @@ void backend_toggle_relax(void)
     backend_set_relax(current == 0 ? 1 : 0);
 }
 
+/**
+ * @brief Set the light brightness
+ *
+ * @param level Brightness percentage (0-100)
+ */
+void backend_set_brightness(uint8_t level)
+{
+    // Clamp level to 0-100
+    if (level > 100) {
+        level = 100;
+    }
+    brightness = level;
+    ESP_LOGD(TAG, "Brightness set to: %d%%", level);
+    backend_save_state();
+    backend_notify(BACKEND_EVENT_BRIGHTNESS_CHANGED, level);
+
+    // Publish for whichever light is on; otherwise it applies on next turn-on
+    if (bright_state != 0) {
+        publish_light_state("bright", 1, level);
+    } else if (relax_state != 0) {
+        publish_light_state("relax", 1, level);
+    }
+}
+
+/**
+ * @brief Get the light brightness
+ *
+ * @return Brightness percentage (0-100)
+ */
+uint8_t backend_get_brightness(void)
+{
+    return brightness;
+}
+
 /**
  * @brief Get the current bright state
  *
@@ -125,10 +530,119 @@ void backend_update_water_level(uint8_t level)
         level = 100;
     }
     water_level = level;
-    printf("[Backend] Water level updated to: %d%%\n", level);
+    ESP_LOGD(TAG, "Water level updated to: %d%%", level);
+
+    water_samples[water_sample_next] = level;
+    water_sample_next = (water_sample_next + 1) % water_avg_window;
+    if (water_sample_count < water_avg_window) {
+        water_sample_count++;
+    }
+
+    // Deferred: writing NVS on every sample would wear the flash
+    backend_schedule_water_save();
+    backend_notify(BACKEND_EVENT_WATER_CHANGED, level);
+
+    // Alert once when dropping into a lower band; rising past the threshold
+    // plus WATER_LEVEL_HYSTERESIS re-arms it, so noise on a boundary can't
+    // fire it again
+    water_band_t band = backend_water_band_next(water_band, level);
+    if (band > water_band && water_alert_cb != NULL) {
+        water_alert_cb(band, level);
+    }
+    water_band = band;
 
     // Update UI
-    ui_update_water_level_async((int)level);
+    ui_update_water_level_async(ui_water_smoothing ? (int)backend_get_water_level_smoothed() : (int)level);
+}
+
+/**
+ * @brief Set water level from a local sensor reading
+ *
+ * @param level Water level percentage (0-100)
+ */
+void backend_set_water_level_local(uint8_t level)
+{
+    backend_update_water_level(level);
+
+    // Publish to MQTT (the clamped value stored by the update above)
+    publish_water_level(water_level);
+}
+
+/**
+ * @brief Mark the tank as full after a refill
+ */
+void backend_water_mark_full(void)
+{
+    ESP_LOGI(TAG, "Tank marked full");
+    backend_set_water_level_local(BACKEND_WATER_LEVEL_FULL);
+}
+
+/**
+ * @brief Register the water alert callback
+ *
+ * @param cb Callback fired when the level enters the low or critical band
+ */
+void backend_register_water_alert_callback(backend_water_alert_cb_t cb)
+{
+    water_alert_cb = cb;
+}
+
+/**
+ * @brief Get the band of the current water level
+ *
+ * @return WATER_BAND_NORMAL, WATER_BAND_LOW or WATER_BAND_CRITICAL
+ */
+water_band_t backend_get_water_band(void)
+{
+    return water_band;
+}
+
+/**
+ * @brief Get the moving average of recent water levels
+ *
+ * @return Average of the last samples (0-100), or the raw level if none yet
+ */
+uint8_t backend_get_water_level_smoothed(void)
+{
+    if (water_sample_count == 0) {
+        return water_level;
+    }
+    uint16_t sum = 0;
+    for (uint8_t i = 0; i < water_sample_count; i++) {
+        sum += water_samples[i];
+    }
+    // Round to nearest
+    return (uint8_t)((sum + water_sample_count / 2) / water_sample_count);
+}
+
+/**
+ * @brief Set the moving average window
+ *
+ * Clears the collected samples.
+ *
+ * @param window Number of samples (1 to BACKEND_WATER_AVG_MAX_WINDOW)
+ */
+void backend_set_water_avg_window(uint8_t window)
+{
+    if (window < 1) {
+        window = 1;
+    }
+    if (window > BACKEND_WATER_AVG_MAX_WINDOW) {
+        window = BACKEND_WATER_AVG_MAX_WINDOW;
+    }
+    water_avg_window = window;
+    water_sample_count = 0;
+    water_sample_next = 0;
+}
+
+/**
+ * @brief Choose whether the UI shows the smoothed or the raw water level
+ *
+ * @param enabled true to send the moving average to the UI
+ */
+void backend_set_ui_water_smoothing(bool enabled)
+{
+    ui_water_smoothing = enabled;
 }
 
 /**
@@ -141,6 +655,93 @@ uint8_t backend_get_water_level(void)
     return water_level;
 }
 
+/**
+ * @brief Serialize the whole backend state as compact JSON
+ *
+ * @param buf Output buffer
+ * @param len Size of buf
+ * @return Length of the full JSON string, as snprintf
+ */
+int backend_to_json(char* buf, size_t len)
+{
+    static const char* const mode_names[] = {
+        [LIGHT_MODE_OFF] = "off",
+        [LIGHT_MODE_BRIGHT] = "bright",
+        [LIGHT_MODE_RELAX] = "relax",
+    };
+    uint8_t level = water_level;
+    uint16_t capacity = tank_capacity_l;
+    if (capacity == 0) {
+        return snprintf(buf, len,
+                        "{\"bright\":%d,\"relax\":%d,\"mode\":\"%s\",\"brightness\":%d,\"water\":%d}",
+                        bright_state, relax_state, mode_names[backend_get_light_mode()],
+                        brightness, level);
+    }
+    
+    // Liters to one decimal, in integer math
+    uint32_t deciliters = (uint32_t)level * capacity / 10;
+    return snprintf(buf, len,
+                    "{\"bright\":%d,\"relax\":%d,\"mode\":\"%s\",\"brightness\":%d,\"water\":%d,"
+                    "\"liters\":%u.%u}",
+                    bright_state, relax_state, mode_names[backend_get_light_mode()],
+                    brightness, level, (unsigned)(deciliters / 10), (unsigned)(deciliters % 10));
+}
+
+/**
+ * @brief Set the water tank capacity
+ *
+ * @param liters Tank size in liters; 0 means unknown
+ */
+void backend_set_tank_capacity(uint16_t liters)
+{
+    tank_capacity_l = liters;
+    ESP_LOGI(TAG, "Tank capacity: %u L", liters);
+}
+
+/**
+ * @brief Get the water tank capacity
+ *
+ * @return Tank size in liters, 0 if not set
+ */
+uint16_t backend_get_tank_capacity(void)
+{
+    return tank_capacity_l;
+}
+
+/**
+ * @brief Get the current water volume
+ *
+ * @return level * capacity / 100 in liters
+ */
+float backend_get_water_liters(void)
+{
+    return (float)water_level * tank_capacity_l / 100.0f;
+}
+
+/**
+ * @brief Set the log level of every component at runtime
+ *
+ * @param level BACKEND_LOG_NONE to BACKEND_LOG_VERBOSE
+ */
+void backend_set_log_level(int level)
+{
+    if (level < BACKEND_LOG_NONE) level = BACKEND_LOG_NONE;
+    if (level > BACKEND_LOG_VERBOSE) level = BACKEND_LOG_VERBOSE;
+    log_level = level;
+    esp_log_level_set("*", (esp_log_level_t)level);
+    ESP_LOGI(TAG, "Log level set to %d", level);
+}
+
+/**
+ * @brief Get the log level last set with backend_set_log_level()
+ *
+ * @return BACKEND_LOG_NONE to BACKEND_LOG_VERBOSE
+ */
+int backend_get_log_level(void)
+{
+    return log_level;
+}
+
 /**
  * @brief Connect to WiFi (placeholder - actual WiFi managed in main)
  *
@@ -153,7 +754,7 @@ void backend_wifi_connect(const char* ssid, const char* password)
     // This is a placeholder for any backend-side WiFi logic
     (void)ssid;
     (void)password;
-    printf("[Backend] WiFi connect placeholder called\n");
+    ESP_LOGD(TAG, "WiFi connect placeholder called");
 }
 
 /**
@@ -166,5 +767,5 @@ void backend_mqtt_connect(const char* broker_url)
     // MQTT connection is handled by ESP-IDF in main.c
     // This is a placeholder for any backend-side MQTT logic
     (void)broker_url;
-    printf("[Backend] MQTT connect placeholder called\n");
+    ESP_LOGD(TAG, "MQTT connect placeholder called");
 }
@@ -16,18 +16,122 @@
 
 #include <stdint.h>
 #include <stdbool.h>
+#include <stddef.h>
 
 #ifdef __cplusplus
 extern "C" {
 #endif
 
+/**
+ * @brief Light mode (bright and relax are mutually exclusive)
+ */
+typedef enum {
+    LIGHT_MODE_OFF = 0,
+    LIGHT_MODE_BRIGHT = 1,
+    LIGHT_MODE_RELAX = 2
+} light_mode_t;
+
+/**
+ * @brief Backend state change event types
+ */
+typedef enum {
+    BACKEND_EVENT_BRIGHT_CHANGED,     /**< value: new bright state (0/1) */
+    BACKEND_EVENT_RELAX_CHANGED,      /**< value: new relax state (0/1) */
+    BACKEND_EVENT_WATER_CHANGED,      /**< value: new water level (0-100) */
+    BACKEND_EVENT_BRIGHTNESS_CHANGED  /**< value: new brightness (0-100) */
+} backend_event_type_t;
+
+/**
+ * @brief Backend state change event
+ */
+typedef struct {
+    backend_event_type_t type;
+    uint8_t value;
+} backend_event_t;
+
+/**
+ * @brief Listener for backend state changes
+ *
+ * Called synchronously from the setter that changed the state, on the
+ * caller's task. Keep it short.
+ */
+typedef void (*backend_listener_t)(const backend_event_t* event, void* user_data);
+
+/** Maximum number of listeners registered with backend_subscribe() */
+#define BACKEND_MAX_LISTENERS 4
+
+/** Water level thresholds (percent) shared with the UI arc colors */
+#define WATER_LEVEL_LOW_THRESHOLD      20
+#define WATER_LEVEL_CRITICAL_THRESHOLD 10
+
+/** Points above a threshold the level must reach to leave the band below it */
+#define WATER_LEVEL_HYSTERESIS         3
+
+/** Water level moving average window (samples) */
+#define BACKEND_WATER_AVG_DEFAULT_WINDOW 5
+#define BACKEND_WATER_AVG_MAX_WINDOW     16
+
+/** Level reported by backend_water_mark_full() */
+#define BACKEND_WATER_LEVEL_FULL 100
+
+/** Buffer size that always fits backend_to_json() output */
+#define BACKEND_JSON_MAX_LEN 112
+
+/** Levels for backend_set_log_level(), numbered like esp_log_level_t */
+#define BACKEND_LOG_NONE    0
+#define BACKEND_LOG_ERROR   1
+#define BACKEND_LOG_WARN    2
+#define BACKEND_LOG_INFO    3
+#define BACKEND_LOG_DEBUG   4
+#define BACKEND_LOG_VERBOSE 5
+
+/**
+ * @brief Water level band
+ *
+ * A band is entered at its threshold but only left once the level is
+ * WATER_LEVEL_HYSTERESIS points above it, see backend_water_band_next().
+ */
+typedef enum {
+    WATER_BAND_NORMAL = 0,   /**< >= WATER_LEVEL_LOW_THRESHOLD */
+    WATER_BAND_LOW,          /**< < WATER_LEVEL_LOW_THRESHOLD */
+    WATER_BAND_CRITICAL      /**< < WATER_LEVEL_CRITICAL_THRESHOLD */
+} water_band_t;
+
+/**
+ * @brief Water alert callback
+ *
+ * Called once when the level drops into a lower band, not again while it stays
+ * there or hovers around the threshold.
+ */
+typedef void (*backend_water_alert_cb_t)(water_band_t band, uint8_t level);
+
 /**
  * @brief Initialize the backend
  *
  * Must be called once before using any other backend functions.
+ * Restores light states, brightness and water level saved in NVS
+ * (requires nvs_flash_init()); defaults are used on first boot.
  */
 void backend_init(void);
 
+/**
+ * @brief Check whether backend_init() has run
+ *
+ * Until then the state is just the defaults, not what was restored from NVS.
+ *
+ * @return true once the backend is initialized
+ */
+bool backend_is_initialized(void);
+
+/**
+ * @brief Register a listener for backend state changes
+ *
+ * @param listener Callback invoked on every state change
+ * @param user_data Passed back to the listener unchanged
+ * @return true if registered, false if BACKEND_MAX_LISTENERS are already registered
+ */
+bool backend_subscribe(backend_listener_t listener, void* user_data);
+
 /**
  * @brief Set the bright light state
  *
@@ -42,6 +146,53 @@ void backend_set_bright(uint8_t state);
  */
 void backend_set_relax(uint8_t state);
 
+/**
+ * @brief Set the light mode
+ *
+ * LIGHT_MODE_OFF turns both lights off; the other modes turn the matching
+ * light on and the other one off.
+ *
+ * @param mode Light mode to apply
+ */
+void backend_set_light_mode(light_mode_t mode);
+
+/**
+ * @brief Get the current light mode
+ *
+ * @return Light mode derived from the bright and relax states
+ */
+light_mode_t backend_get_light_mode(void);
+
+/**
+ * @brief Get the current light mode as a display string
+ *
+ * @param buf Receives "OFF", "BRIGHT" or "RELAX", NUL-terminated
+ * @param len Size of buf (7 bytes fit every mode)
+ * @return Number of characters written (without the NUL), or -1 if buf is NULL or too small
+ */
+int backend_get_light_mode_str(char* buf, size_t len);
+
+/**
+ * @brief Apply a light command received over MQTT
+ *
+ * Sets the light state like backend_set_bright()/backend_set_relax() and
+ * also updates the switch on screen, since the change did not come from it.
+ *
+ * @param mode "bright" or "relax"
+ * @param state 0 for off, 1 for on
+ * @return true if the mode was recognised and applied
+ */
+bool backend_apply_light_command(const char* mode, uint8_t state);
+
+/**
+ * @brief Turn both lights off in one step
+ *
+ * Like backend_set_light_mode(LIGHT_MODE_OFF), but also clears both
+ * switches on screen, for callers other than the UI (MQTT "off" command,
+ * scenes). Publishes only for the light that was on; no-op if both are off.
+ */
+void backend_lights_all_off(void);
+
 /**
  * @brief Toggle the bright light state
  */
@@ -66,13 +217,108 @@ uint8_t backend_get_bright_state(void);
  */
 uint8_t backend_get_relax_state(void);
 
+/**
+ * @brief Set the light brightness
+ *
+ * The level is remembered while the lights are off, so turning a light
+ * back on restores it.
+ *
+ * @param level Brightness percentage (0-100)
+ */
+void backend_set_brightness(uint8_t level);
+
+/**
+ * @brief Get the light brightness
+ *
+ * @return Brightness percentage (0-100)
+ */
+uint8_t backend_get_brightness(void);
+
 /**
  * @brief Update water level from MQTT
  *
+ * The level is saved to NVS up to a minute later, not on every sample.
+ *
  * @param level Water level percentage (0-100)
  */
 void backend_update_water_level(uint8_t level);
 
+/**
+ * @brief Set water level from a local sensor reading
+ *
+ * Updates the UI and publishes the value to MQTT. Use
+ * backend_update_water_level() for values received over MQTT so they
+ * are not echoed back to the broker.
+ *
+ * @param level Water level percentage (0-100)
+ */
+void backend_set_water_level_local(uint8_t level);
+
+/**
+ * @brief Mark the tank as full after a refill
+ *
+ * Sets the level to BACKEND_WATER_LEVEL_FULL, updates the UI and publishes
+ * it, like any local reading. It is not sticky: the next water level
+ * received over MQTT replaces it.
+ */
+void backend_water_mark_full(void);
+
+/**
+ * @brief Register the water alert callback
+ *
+ * @param cb Callback fired when the level enters the low or critical band (NULL to clear)
+ */
+void backend_register_water_alert_callback(backend_water_alert_cb_t cb);
+
+/**
+ * @brief Get the band of the current water level
+ *
+ * This is the band the alerts are based on; the UI colors the arc with it.
+ *
+ * @return WATER_BAND_NORMAL, WATER_BAND_LOW or WATER_BAND_CRITICAL
+ */
+water_band_t backend_get_water_band(void);
+
+/**
+ * @brief Compute the band for a new water level
+ *
+ * Falling into a lower band happens as soon as its threshold is crossed;
+ * rising out of one needs WATER_LEVEL_HYSTERESIS extra points, so a level
+ * hovering on a boundary keeps its band.
+ *
+ * @param current Band before this reading
+ * @param level Water level percentage (0-100)
+ * @return The new band
+ */
+water_band_t backend_water_band_next(water_band_t current, uint8_t level);
+
+/**
+ * @brief Get the moving average of recent water levels
+ *
+ * backend_get_water_level() keeps returning the latest raw value.
+ *
+ * @return Average of the last samples (0-100), or the raw level if none yet
+ */
+uint8_t backend_get_water_level_smoothed(void);
+
+/**
+ * @brief Set the moving average window
+ *
+ * Clears the collected samples.
+ *
+ * @param window Number of samples (1 to BACKEND_WATER_AVG_MAX_WINDOW, default 5)
+ */
+void backend_set_water_avg_window(uint8_t window);
+
+/**
+ * @brief Choose whether the UI shows the smoothed or the raw water level
+ *
+ * Off by default.
+ *
+ * @param enabled true to send the moving average to the UI
+ */
+void backend_set_ui_water_smoothing(bool enabled);
+
 /**
  * @brief Get current water level
  *
@@ -80,6 +326,58 @@ void backend_update_water_level(uint8_t level);
  */
 uint8_t backend_get_water_level(void);
 
+/**
+ * @brief Set the water tank capacity
+ *
+ * @param liters Tank size in liters; 0 (the default) means unknown
+ */
+void backend_set_tank_capacity(uint16_t liters);
+
+/**
+ * @brief Get the water tank capacity
+ *
+ * @return Tank size in liters, 0 if not set
+ */
+uint16_t backend_get_tank_capacity(void);
+
+/**
+ * @brief Get the current water volume
+ *
+ * @return level * capacity / 100 in liters, 0 when no capacity is set
+ */
+float backend_get_water_liters(void);
+
+/**
+ * @brief Serialize the whole backend state as compact JSON
+ *
+ * {"bright":0|1,"relax":0|1,"mode":"off|bright|relax","brightness":0-100,"water":0-100}
+ * plus "liters":N.N when a tank capacity is set
+ *
+ * @param buf Output buffer (always NUL-terminated when len > 0)
+ * @param len Size of buf; BACKEND_JSON_MAX_LEN is always enough
+ * @return Length of the full JSON string, as snprintf (>= len means truncated)
+ */
+int backend_to_json(char* buf, size_t len);
+
+/**
+ * @brief Set the log level of every component at runtime
+ *
+ * Per-change logs (light toggles, brightness, water levels, incoming MQTT
+ * messages) are at BACKEND_LOG_DEBUG, so the default BACKEND_LOG_INFO keeps
+ * the serial console quiet. Levels above CONFIG_LOG_MAXIMUM_LEVEL are
+ * compiled out and cannot be enabled at runtime.
+ *
+ * @param level BACKEND_LOG_NONE to BACKEND_LOG_VERBOSE (clamped)
+ */
+void backend_set_log_level(int level);
+
+/**
+ * @brief Get the log level last set with backend_set_log_level()
+ *
+ * @return BACKEND_LOG_NONE to BACKEND_LOG_VERBOSE
+ */
+int backend_get_log_level(void);
+
 /**
  * @brief Connect to WiFi (placeholder - actual WiFi managed in main)
  *
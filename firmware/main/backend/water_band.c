@@ -0,0 +1,39 @@
+/**
+ * @file water_band.c
+ * @brief Water level band with hysteresis
+ *
+ * Kept free of ESP-IDF dependencies so it also builds in the host tests.
+ */
+
+#include "backend.h"
+
+/**
+ * @brief Compute the band for a new water level
+ *
+ * @param current Band before this reading
+ * @param level Water level percentage (0-100)
+ * @return The new band
+ */
+water_band_t backend_water_band_next(water_band_t current, uint8_t level)
+{
+    // Falling: switch as soon as a threshold is crossed
+    water_band_t band = WATER_BAND_NORMAL;
+    if (level < WATER_LEVEL_CRITICAL_THRESHOLD) {
+        band = WATER_BAND_CRITICAL;
+    } else if (level < WATER_LEVEL_LOW_THRESHOLD) {
+        band = WATER_BAND_LOW;
+    }
+    if (band >= current) {
+        return band;
+    }
+
+    // Rising: only leave a band once clearly above its threshold
+    if (current == WATER_BAND_CRITICAL &&
+        level < WATER_LEVEL_CRITICAL_THRESHOLD + WATER_LEVEL_HYSTERESIS) {
+        return WATER_BAND_CRITICAL;
+    }
+    if (level < WATER_LEVEL_LOW_THRESHOLD + WATER_LEVEL_HYSTERESIS) {
+        return WATER_BAND_LOW;
+    }
+    return WATER_BAND_NORMAL;
+}
@@ -0,0 +1,81 @@
+#include "diagnostics.h"
+#include "esp_log.h"
+#include "esp_timer.h"
+#include "esp_heap_caps.h"
+#include "sdkconfig.h"
+
+static const char *TAG = "DIAG";
+
+static esp_reset_reason_t reset_reason = ESP_RST_UNKNOWN;
+static bool memory_low = false;
+
+void diagnostics_init(void)
+{
+    reset_reason = esp_reset_reason();
+    
+    // Unexpected resets are worth a warning when reading field logs
+    switch (reset_reason) {
+        case ESP_RST_POWERON:
+        case ESP_RST_SW:
+        case ESP_RST_DEEPSLEEP:
+            ESP_LOGI(TAG, "Reset reason: %s", diagnostics_reset_reason_str(reset_reason));
+            break;
+        default:
+            ESP_LOGW(TAG, "Reset reason: %s", diagnostics_reset_reason_str(reset_reason));
+            break;
+    }
+}
+
+uint32_t diagnostics_get_uptime_s(void)
+{
+    // esp_timer starts counting at boot
+    return (uint32_t)(esp_timer_get_time() / 1000000);
+}
+
+esp_reset_reason_t diagnostics_get_reset_reason(void)
+{
+    return reset_reason;
+}
+
+const char *diagnostics_reset_reason_str(esp_reset_reason_t reason)
+{
+    switch (reason) {
+        case ESP_RST_POWERON:   return "poweron";
+        case ESP_RST_EXT:       return "external";
+        case ESP_RST_SW:        return "software";
+        case ESP_RST_PANIC:     return "panic";
+        case ESP_RST_INT_WDT:   return "int_wdt";
+        case ESP_RST_TASK_WDT:  return "task_wdt";
+        case ESP_RST_WDT:       return "wdt";
+        case ESP_RST_DEEPSLEEP: return "deepsleep";
+        case ESP_RST_BROWNOUT:  return "brownout";
+        case ESP_RST_SDIO:      return "sdio";
+        case ESP_RST_UNKNOWN:
+        default:                return "unknown";
+    }
+}
+
+void diagnostics_check_memory(diagnostics_memory_t *out)
+{
+    diagnostics_memory_t mem = {
+        .free_internal = (uint32_t)heap_caps_get_free_size(MALLOC_CAP_INTERNAL),
+        .min_free_internal = (uint32_t)heap_caps_get_minimum_free_size(MALLOC_CAP_INTERNAL),
+        .largest_internal = (uint32_t)heap_caps_get_largest_free_block(MALLOC_CAP_INTERNAL),
+        .free_psram = (uint32_t)heap_caps_get_free_size(MALLOC_CAP_SPIRAM),
+    };
+    mem.low = mem.free_internal < CONFIG_DIAG_LOW_HEAP_BYTES;
+    
+    // Log on the transitions only; this runs every few seconds
+    if (mem.low && !memory_low) {
+        ESP_LOGW(TAG, "Low internal heap: %u bytes free (largest block %u, minimum %u)",
+                 (unsigned)mem.free_internal, (unsigned)mem.largest_internal,
+                 (unsigned)mem.min_free_internal);
+    } else if (!mem.low && memory_low) {
+        ESP_LOGI(TAG, "Internal heap recovered: %u bytes free", (unsigned)mem.free_internal);
+    }
+    memory_low = mem.low;
+    
+    if (out != NULL) {
+        *out = mem;
+    }
+}
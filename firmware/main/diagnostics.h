@@ -0,0 +1,33 @@
+#ifndef DIAGNOSTICS_H
+#define DIAGNOSTICS_H
+
+#include <stdint.h>
+#include <stdbool.h>
+#include "esp_system.h"
+
+// Heap figures from diagnostics_check_memory(), in bytes
+typedef struct {
+    uint32_t free_internal;        // Free internal RAM (WiFi, MQTT, task stacks)
+    uint32_t min_free_internal;    // Lowest free internal RAM since boot
+    uint32_t largest_internal;     // Largest allocatable internal block
+    uint32_t free_psram;           // Free PSRAM (framebuffers), 0 without PSRAM
+    bool low;                      // free_internal below CONFIG_DIAG_LOW_HEAP_BYTES
+} diagnostics_memory_t;
+
+// Record the reset reason and boot time; call once, early in app_main
+void diagnostics_init(void);
+
+// Seconds since boot
+uint32_t diagnostics_get_uptime_s(void);
+
+// Why the chip last reset (captured by diagnostics_init)
+esp_reset_reason_t diagnostics_get_reset_reason(void);
+
+// Short lowercase name for a reset reason ("poweron", "brownout", "panic", ...)
+const char *diagnostics_reset_reason_str(esp_reset_reason_t reason);
+
+// Read the heap figures (out may be NULL). Warns once when free internal RAM
+// drops below CONFIG_DIAG_LOW_HEAP_BYTES and logs again when it recovers.
+void diagnostics_check_memory(diagnostics_memory_t *out);
+
+#endif // DIAGNOSTICS_H
@@ -5,11 +5,20 @@
 #include "esp_lcd_panel_ops.h"
 #include "driver/gpio.h"
 #include "driver/i2c.h"
+#include "driver/ledc.h"
 #include "esp_log.h"
 #include "freertos/FreeRTOS.h"
 #include "freertos/task.h"
+#include "freertos/semphr.h"
 #include "esp_rom_sys.h"
+#include "soc/soc_caps.h"
+#include "esp_timer.h"
+#include "esp_heap_caps.h"
 #include <string.h>
+#if CONFIG_DISPLAY_FB_CAPTURE
+#include <stdio.h>
+#include "mbedtls/base64.h"
+#endif
 
 static const char *TAG = "DISPLAY";
 
@@ -42,15 +51,13 @@ static const char *TAG = "DISPLAY";
 #define LCD_GPIO_PCLK    21
 #define LCD_GPIO_BL      45  // Backlight (active high)
 
-// Display timing parameters for 480x480 ST7701
-// From: sensecap_indicator_board.c timing configuration
-#define HSYNC_BACK_PORCH     50
-#define HSYNC_FRONT_PORCH    10
-#define HSYNC_PULSE_WIDTH     8
-#define VSYNC_BACK_PORCH     50
-#define VSYNC_FRONT_PORCH    10
-#define VSYNC_PULSE_WIDTH     8
-#define LCD_FREQ         (16000000)  // 16MHz
+// Backlight PWM (LEDC) configuration
+#define BL_LEDC_TIMER        LEDC_TIMER_0
+#define BL_LEDC_CHANNEL      LEDC_CHANNEL_0
+#define BL_LEDC_MODE         LEDC_LOW_SPEED_MODE
+#define BL_LEDC_DUTY_RES     LEDC_TIMER_10_BIT
+#define BL_LEDC_DUTY_MAX     ((1 << 10) - 1)
+#define BL_LEDC_FREQ_HZ      5000
 
 // =============================================================================
 // OFFICIAL SDK REFERENCE: components/bsp/src/boards/lcd_panel_config.c
@@ -82,10 +89,27 @@ static const char *TAG = "DISPLAY";
 #define I2C_MASTER_SCL_IO   40          // GPIO_I2C_SCL
 #define I2C_MASTER_FREQ_HZ  400000
 
+// Upper bound for a flush waiting on the frame end (one frame is ~30 ms at
+// the slowest pixel clocks), so a stalled panel can't hang the LVGL task
+#define DISPLAY_VSYNC_TIMEOUT_MS  100
+
 static lv_disp_drv_t disp_drv;
+static lv_disp_t *disp = NULL;
 static lv_disp_draw_buf_t draw_buf;
 static lv_color_t *buf1 = NULL;
+static uint16_t *rot_buf = NULL;  // Rotated copy of a flushed area, see display_flush_cb
 static esp_lcd_panel_handle_t panel_handle = NULL;
+static bool double_buffered = false;
+static bool draw_buf_in_psram = false;
+static uint8_t target_fps = 0;
+static void *front_fb = NULL;  // Framebuffer currently scanned out by the panel
+static SemaphoreHandle_t vsync_sem = NULL;  // Given by the panel at the end of every frame
+static uint8_t backlight_percent = 0;
+static bool inversion_on = true;
+static uint8_t madctl = DISPLAY_MADCTL_DEFAULT;
+static bool display_asleep = false;
+static uint8_t backlight_before_sleep = 100;
+static display_config_t display_config = DISPLAY_CONFIG_DEFAULT();
 
 // IO Expander state
 static uint16_t io_expander_output = 0;
@@ -182,6 +206,7 @@ static void tca9535_set_level(uint8_t pin, bool level)
 #define MOSI(n) gpio_set_level(SPI_GPIO_MOSI, n)
 #define Delay(t) vTaskDelay(pdMS_TO_TICKS(t))
 #define udelay(_t) esp_rom_delay_us(_t)
+#define SPI_DELAY() udelay(display_config.spi_delay_us)
 
 static void spi_init_gpio(void)
 {
@@ -209,9 +234,9 @@ static void SPI_SendData(unsigned short i)
         }
         i = i << 1;
         CLK(1);
-        udelay(10);
+        SPI_DELAY();
         CLK(0);
-        udelay(10);
+        SPI_DELAY();
     }
 }
 
@@ -219,45 +244,45 @@ static void SPI_WriteComm(unsigned short c)
 {
     // Send command (bit 8 = 0 for command)
     CS(0);
-    udelay(10);
+    SPI_DELAY();
     CLK(0);
-    udelay(10);
+    SPI_DELAY();
     
     SPI_SendData(((c >> 8) & 0x00FF) | 0x2000);  // High byte with command bit
     
     CLK(1);
-    udelay(10);
+    SPI_DELAY();
     CLK(0);
     
     CS(1);
-    udelay(10);
+    SPI_DELAY();
     CS(0);
-    udelay(10);
+    SPI_DELAY();
     
     SPI_SendData((c & 0x00FF));  // Low byte
     CS(1);
-    udelay(10);
+    SPI_DELAY();
 }
 
 static void SPI_WriteData(unsigned short d)
 {
     // Send data (bit 8 = 1 for data)
     CS(0);
-    udelay(10);
+    SPI_DELAY();
     CLK(0);
-    udelay(10);
+    SPI_DELAY();
     
     d &= 0x00FF;
     d |= 0x0100;  // Set data bit
     SPI_SendData(d);
     
     CLK(1);
-    udelay(10);
+    SPI_DELAY();
     CLK(0);
-    udelay(10);
+    SPI_DELAY();
     
     CS(1);
-    udelay(10);
+    SPI_DELAY();
 }
 
 // =============================================================================
@@ -265,7 +290,7 @@ static void SPI_WriteData(unsigned short d)
 // Reference: components/bsp/src/boards/lcd_panel_config.c lcd_panel_st7701s_init()
 // =============================================================================
 
-static void st7701s_init_sequence(void)
+static void st7701s_init_sequence(const display_gamma_t *gamma)
 {
     ESP_LOGI(TAG, "Starting ST7701S initialization sequence");
     
@@ -284,7 +309,7 @@ static void st7701s_init_sequence(void)
 
     // Display resolution
     SPI_WriteComm(0xC0);
-    SPI_WriteData(0x3B);  // 480*480
+    SPI_WriteData((display_config.v_res / 8) - 1);  // Line count in units of 8 (0x3B for 480)
     SPI_WriteData(0x00);
 
     SPI_WriteComm(0xC1);
@@ -301,42 +326,16 @@ static void st7701s_init_sequence(void)
     SPI_WriteComm(0xCD);
     SPI_WriteData(0x08);
 
-    // Gamma settings
+    // Gamma settings (positive 0xB0, negative 0xB1)
     SPI_WriteComm(0xB0);
-    SPI_WriteData(0x00);
-    SPI_WriteData(0x11);
-    SPI_WriteData(0x18);
-    SPI_WriteData(0x0E);
-    SPI_WriteData(0x11);
-    SPI_WriteData(0x06);
-    SPI_WriteData(0x07);
-    SPI_WriteData(0x08);
-    SPI_WriteData(0x07);
-    SPI_WriteData(0x22);
-    SPI_WriteData(0x04);
-    SPI_WriteData(0x12);
-    SPI_WriteData(0x0F);
-    SPI_WriteData(0xAA);
-    SPI_WriteData(0x31);
-    SPI_WriteData(0x18);
+    for (int i = 0; i < DISPLAY_GAMMA_LEN; i++) {
+        SPI_WriteData(gamma->positive[i]);
+    }
 
     SPI_WriteComm(0xB1);
-    SPI_WriteData(0x00);
-    SPI_WriteData(0x11);
-    SPI_WriteData(0x19);
-    SPI_WriteData(0x0E);
-    SPI_WriteData(0x12);
-    SPI_WriteData(0x07);
-    SPI_WriteData(0x08);
-    SPI_WriteData(0x08);
-    SPI_WriteData(0x08);
-    SPI_WriteData(0x22);
-    SPI_WriteData(0x04);
-    SPI_WriteData(0x11);
-    SPI_WriteData(0x11);
-    SPI_WriteData(0xA9);
-    SPI_WriteData(0x32);
-    SPI_WriteData(0x18);
+    for (int i = 0; i < DISPLAY_GAMMA_LEN; i++) {
+        SPI_WriteData(gamma->negative[i]);
+    }
 
     // Command 2 BK1 (PAGE2)
     SPI_WriteComm(0xFF);
@@ -495,9 +494,9 @@ static void st7701s_init_sequence(void)
     SPI_WriteData(0x98);
     SPI_WriteData(0xBA);
 
-    // Memory access control
+    // Memory access control (0x10 plus any mirror bits from display_set_mirror)
     SPI_WriteComm(0x36);
-    SPI_WriteData(0x10);
+    SPI_WriteData(madctl);
 
     // Command 2 BK3 (PAGE3)
     SPI_WriteComm(0xFF);
@@ -522,8 +521,8 @@ static void st7701s_init_sequence(void)
     SPI_WriteComm(0x3A);
     SPI_WriteData(0x60);  // 0x70 RGB888, 0x60 RGB666, 0x50 RGB565
 
-    // Display Inversion On
-    SPI_WriteComm(0x21);
+    // Display Inversion On (0x21) / Off (0x20)
+    SPI_WriteComm(inversion_on ? 0x21 : 0x20);
 
     // Sleep Out
     SPI_WriteComm(0x11);
@@ -545,8 +544,20 @@ static void st7701s_init_sequence(void)
 // RGB DISPLAY INTERFACE
 // =============================================================================
 
-void display_init(void)
+// Panel ISR at the end of every frame
+static bool IRAM_ATTR display_on_vsync(esp_lcd_panel_handle_t panel,
+                                        const esp_lcd_rgb_panel_event_data_t *edata, void *user_ctx)
+{
+    BaseType_t woken = pdFALSE;
+    xSemaphoreGiveFromISR(vsync_sem, &woken);
+    return woken == pdTRUE;
+}
+
+void display_init(const display_config_t *config)
 {
+    // Keep a copy so callers can pass a temporary
+    display_config = config ? *config : (display_config_t)DISPLAY_CONFIG_DEFAULT();
+    
     ESP_LOGI(TAG, "Initializing SenseCAP Indicator D1 Display");
     ESP_LOGI(TAG, "Reference: Seeed Studio SDK - sensecap_indicator_esp32");
     
@@ -558,17 +569,33 @@ void display_init(void)
     // Reference: lcd_panel_config.c init_gpios()
     spi_init_gpio();
     
-    // Step 3: Configure backlight
-    gpio_config_t bk_gpio_config = {
-        .mode = GPIO_MODE_OUTPUT,
-        .pin_bit_mask = 1ULL << LCD_GPIO_BL
+    // Step 3: Configure backlight PWM
+    ledc_timer_config_t bl_timer_config = {
+        .speed_mode = BL_LEDC_MODE,
+        .duty_resolution = BL_LEDC_DUTY_RES,
+        .timer_num = BL_LEDC_TIMER,
+        .freq_hz = BL_LEDC_FREQ_HZ,
+        .clk_cfg = LEDC_AUTO_CLK,
     };
-    ESP_ERROR_CHECK(gpio_config(&bk_gpio_config));
-    gpio_set_level(LCD_GPIO_BL, 0);  // Off initially
+    ESP_ERROR_CHECK(ledc_timer_config(&bl_timer_config));
+    
+    ledc_channel_config_t bl_channel_config = {
+        .gpio_num = LCD_GPIO_BL,
+        .speed_mode = BL_LEDC_MODE,
+        .channel = BL_LEDC_CHANNEL,
+        .timer_sel = BL_LEDC_TIMER,
+        .duty = 0,  // Off initially
+        .hpoint = 0,
+    };
+    ESP_ERROR_CHECK(ledc_channel_config(&bl_channel_config));
+    ESP_ERROR_CHECK(ledc_fade_func_install(0));
     
     // Step 4: Initialize ST7701S via SPI
     // Reference: lcd_panel_config.c lcd_panel_st7701s_init()
-    st7701s_init_sequence();
+    int64_t init_start = esp_timer_get_time();
+    st7701s_init_sequence(&display_config.gamma);
+    ESP_LOGI(TAG, "ST7701S init took %lld ms (SPI delay %u us)",
+             (esp_timer_get_time() - init_start) / 1000, display_config.spi_delay_us);
     
     // Step 5: Configure RGB panel
     // Reference: bsp_lcd.c bsp_lcd_init() with RGB interface
@@ -587,68 +614,460 @@ void display_init(void)
             LCD_GPIO_DATA12, LCD_GPIO_DATA13, LCD_GPIO_DATA14, LCD_GPIO_DATA15,
         },
         .timings = {
-            .pclk_hz = LCD_FREQ,
-            .h_res = DISP_HOR_RES,
-            .v_res = DISP_VER_RES,
-            .hsync_back_porch = HSYNC_BACK_PORCH,
-            .hsync_front_porch = HSYNC_FRONT_PORCH,
-            .hsync_pulse_width = HSYNC_PULSE_WIDTH,
-            .vsync_back_porch = VSYNC_BACK_PORCH,
-            .vsync_front_porch = VSYNC_FRONT_PORCH,
-            .vsync_pulse_width = VSYNC_PULSE_WIDTH,
+            .pclk_hz = display_config.timing.pclk_hz,
+            .h_res = display_config.h_res,
+            .v_res = display_config.v_res,
+            .hsync_back_porch = display_config.timing.hsync_back_porch,
+            .hsync_front_porch = display_config.timing.hsync_front_porch,
+            .hsync_pulse_width = display_config.timing.hsync_pulse_width,
+            .vsync_back_porch = display_config.timing.vsync_back_porch,
+            .vsync_front_porch = display_config.timing.vsync_front_porch,
+            .vsync_pulse_width = display_config.timing.vsync_pulse_width,
             .flags.pclk_active_neg = false,
         },
         .flags.fb_in_psram = 1,
-        .num_fbs = 1,
+        .num_fbs = 2,
     };
     
-    ESP_LOGI(TAG, "Creating RGB panel: %dx%d @ %d Hz", DISP_HOR_RES, DISP_VER_RES, LCD_FREQ);
-    ESP_ERROR_CHECK(esp_lcd_new_rgb_panel(&panel_config, &panel_handle));
+    ESP_LOGI(TAG, "Creating RGB panel: %ux%u @ %lu Hz", display_config.h_res, display_config.v_res,
+             (unsigned long)display_config.timing.pclk_hz);
+    
+    // Two framebuffers let LVGL render into the back buffer while the panel
+    // scans out the front one. Fall back to a single buffer if PSRAM is short.
+    esp_err_t ret = esp_lcd_new_rgb_panel(&panel_config, &panel_handle);
+    if (ret == ESP_OK) {
+        double_buffered = true;
+    } else {
+        ESP_LOGW(TAG, "Double framebuffer allocation failed (%s), using single buffer",
+                 esp_err_to_name(ret));
+        panel_config.num_fbs = 1;
+        ESP_ERROR_CHECK(esp_lcd_new_rgb_panel(&panel_config, &panel_handle));
+        double_buffered = false;
+    }
+    
+    // Frame-end notification, so flushes can wait for a buffer swap to land
+    vsync_sem = xSemaphoreCreateBinary();
+    const esp_lcd_rgb_panel_event_callbacks_t panel_cbs = {
+        .on_vsync = display_on_vsync,
+    };
+    if (vsync_sem == NULL ||
+        esp_lcd_rgb_panel_register_event_callbacks(panel_handle, &panel_cbs, NULL) != ESP_OK) {
+        ESP_LOGW(TAG, "VSYNC callback unavailable, flushes won't wait for the frame end");
+        if (vsync_sem != NULL) {
+            vSemaphoreDelete(vsync_sem);
+            vsync_sem = NULL;
+        }
+    }
+    
     ESP_ERROR_CHECK(esp_lcd_panel_reset(panel_handle));
     ESP_ERROR_CHECK(esp_lcd_panel_init(panel_handle));
     ESP_ERROR_CHECK(esp_lcd_panel_disp_on_off(panel_handle, true));
     
     // Turn on backlight
-    gpio_set_level(LCD_GPIO_BL, 1);
+    display_set_backlight(100);
     
     ESP_LOGI(TAG, "Display initialization complete");
 }
 
+void display_set_backlight(uint8_t percent)
+{
+    if (percent > 100) {
+        percent = 100;
+    }
+    
+    uint32_t duty = (BL_LEDC_DUTY_MAX * percent) / 100;
+#if SOC_LEDC_SUPPORT_FADE_STOP
+    // Cancel a running fade so it doesn't overwrite this level
+    ledc_fade_stop(BL_LEDC_MODE, BL_LEDC_CHANNEL);
+#endif
+    ESP_ERROR_CHECK(ledc_set_duty(BL_LEDC_MODE, BL_LEDC_CHANNEL, duty));
+    ESP_ERROR_CHECK(ledc_update_duty(BL_LEDC_MODE, BL_LEDC_CHANNEL));
+    backlight_percent = percent;
+    
+    ESP_LOGD(TAG, "Backlight set to %d%%", percent);
+}
+
+void display_fade_backlight(uint8_t percent, uint32_t duration_ms, bool wait)
+{
+    if (percent > 100) {
+        percent = 100;
+    }
+    if (duration_ms == 0) {
+        display_set_backlight(percent);
+        return;
+    }
+    
+#if SOC_LEDC_SUPPORT_FADE_STOP
+    ledc_fade_stop(BL_LEDC_MODE, BL_LEDC_CHANNEL);
+#endif
+    // The LEDC peripheral ramps the duty in hardware, so no task is involved
+    uint32_t duty = (BL_LEDC_DUTY_MAX * percent) / 100;
+    ESP_ERROR_CHECK(ledc_set_fade_with_time(BL_LEDC_MODE, BL_LEDC_CHANNEL, duty, duration_ms));
+    ESP_ERROR_CHECK(ledc_fade_start(BL_LEDC_MODE, BL_LEDC_CHANNEL,
+                                    wait ? LEDC_FADE_WAIT_DONE : LEDC_FADE_NO_WAIT));
+    backlight_percent = percent;
+    
+    ESP_LOGD(TAG, "Backlight fading to %d%% over %lu ms", percent, (unsigned long)duration_ms);
+}
+
+uint8_t display_get_backlight(void)
+{
+    return backlight_percent;
+}
+
+void display_set_inversion(bool on)
+{
+    // ST7701S keeps listening on the bit-banged SPI after init, so the
+    // inversion command can be resent at any time
+    SPI_WriteComm(on ? 0x21 : 0x20);
+    CS(1);
+    CLK(1);
+    MOSI(1);
+    inversion_on = on;
+    
+    ESP_LOGI(TAG, "Display inversion %s", on ? "on" : "off");
+}
+
+bool display_get_inversion(void)
+{
+    return inversion_on;
+}
+
+void display_set_mirror(bool mirror_x, bool mirror_y)
+{
+    madctl = DISPLAY_MADCTL_DEFAULT;
+    if (mirror_x) madctl |= DISPLAY_MADCTL_MIRROR_X;
+    if (mirror_y) madctl |= DISPLAY_MADCTL_MIRROR_Y;
+    if (!io_expander_initialized) {
+        // Before display_init: the init sequence writes it
+        return;
+    }
+    
+    // MADCTL is a CMD1 register, which is selected again at the end of init
+    SPI_WriteComm(0x36);
+    SPI_WriteData(madctl);
+    CS(1);
+    CLK(1);
+    MOSI(1);
+    
+    ESP_LOGI(TAG, "Display mirror x=%d y=%d (MADCTL 0x%02X)", mirror_x, mirror_y, madctl);
+}
+
+void display_get_mirror(bool *mirror_x, bool *mirror_y)
+{
+    if (mirror_x) *mirror_x = (madctl & DISPLAY_MADCTL_MIRROR_X) != 0;
+    if (mirror_y) *mirror_y = (madctl & DISPLAY_MADCTL_MIRROR_Y) != 0;
+}
+
+void display_sleep(void)
+{
+    if (display_asleep) {
+        return;
+    }
+    
+    backlight_before_sleep = backlight_percent;
+    // Wait for the fade so the panel goes dark before Display Off
+    display_fade_backlight(0, DISPLAY_BACKLIGHT_FADE_MS, true);
+    
+    // Display Off, then Sleep In
+    SPI_WriteComm(0x28);
+    SPI_WriteComm(0x10);
+    Delay(5);
+    CS(1);
+    CLK(1);
+    MOSI(1);
+    
+    display_asleep = true;
+    ESP_LOGI(TAG, "Display sleeping");
+}
+
+void display_wake(void)
+{
+    if (!display_asleep) {
+        return;
+    }
+    
+    // Sleep Out needs 120ms before the panel accepts Display On
+    SPI_WriteComm(0x11);
+    Delay(120);
+    SPI_WriteComm(0x29);
+    CS(1);
+    CLK(1);
+    MOSI(1);
+    
+    display_fade_backlight(backlight_before_sleep, DISPLAY_BACKLIGHT_FADE_MS, false);
+    display_asleep = false;
+    ESP_LOGI(TAG, "Display awake");
+}
+
+bool display_is_asleep(void)
+{
+    return display_asleep;
+}
+
+// Tell LVGL the flush is done. After the last area of a refresh, first wait
+// for the frame end: only then has the panel switched to (or finished
+// reading) the new data, and LVGL may render into the other buffer again.
+static void display_flush_done(lv_disp_drv_t *drv)
+{
+    if (vsync_sem != NULL && lv_disp_flush_is_last(drv)) {
+        xSemaphoreTake(vsync_sem, pdMS_TO_TICKS(DISPLAY_VSYNC_TIMEOUT_MS));
+    }
+    lv_disp_flush_ready(drv);
+}
+
 void display_flush_cb(lv_disp_drv_t *drv, const lv_area_t *area, lv_color_t *color_map)
 {
+    // Drop a frame end that happened before this flush
+    if (vsync_sem != NULL && lv_disp_flush_is_last(drv)) {
+        xSemaphoreTake(vsync_sem, 0);
+    }
+    
+    // Rotated: LVGL rendered the area in its rotated frame into buf1, so map
+    // it onto the panel here and copy it into the scanned-out framebuffer
+    if (drv->rotated != LV_DISP_ROT_NONE && rot_buf != NULL) {
+        const display_area_t logical = { area->x1, area->y1, area->x2, area->y2 };
+        display_area_t panel;
+        display_rotation_map_area((display_rotation_t)drv->rotated,
+                                  display_config.h_res, display_config.v_res, &logical, &panel);
+        display_rotation_copy((display_rotation_t)drv->rotated,
+                              display_config.h_res, display_config.v_res,
+                              &logical, (const uint16_t *)color_map, rot_buf);
+        esp_lcd_panel_draw_bitmap(panel_handle, panel.x1, panel.y1,
+                                  panel.x2 + 1, panel.y2 + 1, rot_buf);
+        display_flush_done(drv);
+        return;
+    }
+    
+    // When double buffered, color_map is one of the panel framebuffers and
+    // draw_bitmap just swaps which buffer is scanned out (no copy)
     esp_lcd_panel_draw_bitmap(panel_handle, 
                               area->x1, area->y1, 
                               area->x2 + 1, area->y2 + 1, 
                               color_map);
-    lv_disp_flush_ready(drv);
+    if (double_buffered) {
+        front_fb = color_map;
+    }
+    display_flush_done(drv);
+}
+
+// Allocate a full-screen pixel buffer, from PSRAM if possible
+static void *display_alloc_buffer(size_t bytes, bool *in_psram)
+{
+    void *buf = heap_caps_malloc(bytes, MALLOC_CAP_SPIRAM | MALLOC_CAP_8BIT);
+    *in_psram = (buf != NULL);
+    if (buf == NULL) {
+        ESP_LOGW(TAG, "PSRAM not available, using internal RAM (degraded: little heap left for WiFi/MQTT)");
+        buf = heap_caps_malloc(bytes, MALLOC_CAP_INTERNAL | MALLOC_CAP_8BIT);
+    }
+    return buf;
 }
 
 void display_driver_init(void)
 {
     ESP_LOGI(TAG, "Initializing LVGL display driver");
     
-    size_t buffer_size = DISP_HOR_RES * DISP_VER_RES;
+    size_t buffer_size = (size_t)display_config.h_res * display_config.v_res;
+    
+    lv_disp_drv_init(&disp_drv);
     
-    // Allocate from PSRAM
-    buf1 = heap_caps_malloc(buffer_size * sizeof(lv_color_t), MALLOC_CAP_SPIRAM | MALLOC_CAP_8BIT);
-    if (buf1 == NULL) {
-        ESP_LOGW(TAG, "PSRAM not available, using internal RAM");
-        buf1 = heap_caps_malloc(buffer_size * sizeof(lv_color_t), MALLOC_CAP_INTERNAL | MALLOC_CAP_8BIT);
+    if (double_buffered) {
+        // Render straight into the panel's own framebuffers and swap on flush.
+        // Every frame must be complete, so LVGL redraws the full screen.
+        void *fb0 = NULL;
+        void *fb1 = NULL;
+        ESP_ERROR_CHECK(esp_lcd_rgb_panel_get_frame_buffer(panel_handle, 2, &fb0, &fb1));
+        lv_disp_draw_buf_init(&draw_buf, fb0, fb1, buffer_size);
+        front_fb = fb0;
+        disp_drv.full_refresh = 1;
+        draw_buf_in_psram = true;   // Panel framebuffers are created with fb_in_psram
+        ESP_LOGI(TAG, "Using double-buffered rendering");
+    } else {
+        buf1 = display_alloc_buffer(buffer_size * sizeof(lv_color_t), &draw_buf_in_psram);
         if (buf1 == NULL) {
             ESP_LOGE(TAG, "Failed to allocate display buffer");
             return;
         }
+        lv_disp_draw_buf_init(&draw_buf, buf1, NULL, buffer_size);
+        ESP_ERROR_CHECK(esp_lcd_rgb_panel_get_frame_buffer(panel_handle, 1, &front_fb));
+        // Partial refresh: LVGL tracks invalidated areas and flush_cb only pushes
+        // the bounding boxes of what actually changed into the panel framebuffer
+        disp_drv.full_refresh = 0;
     }
     
-    lv_disp_draw_buf_init(&draw_buf, buf1, NULL, buffer_size);
-    
-    lv_disp_drv_init(&disp_drv);
-    disp_drv.hor_res = DISP_HOR_RES;
-    disp_drv.ver_res = DISP_VER_RES;
+    disp_drv.hor_res = display_config.h_res;
+    disp_drv.ver_res = display_config.v_res;
     disp_drv.flush_cb = display_flush_cb;
     disp_drv.draw_buf = &draw_buf;
-    disp_drv.full_refresh = 1;
-    lv_disp_drv_register(&disp_drv);
+    // Rotation is done in display_flush_cb rather than by LVGL's sw_rotate,
+    // which can't rotate a full refresh into the panel framebuffers. LVGL
+    // still maps pointer input from disp_drv.rotated.
+    disp_drv.sw_rotate = 0;
+    disp = lv_disp_drv_register(&disp_drv);
+    display_set_target_fps(CONFIG_DISPLAY_ACTIVE_FPS);
     
+    ESP_LOGI(TAG, "Draw buffer in %s; free heap: internal %u bytes, PSRAM %u bytes",
+             draw_buf_in_psram ? "PSRAM" : "internal RAM",
+             (unsigned)heap_caps_get_free_size(MALLOC_CAP_INTERNAL),
+             (unsigned)heap_caps_get_free_size(MALLOC_CAP_SPIRAM));
     ESP_LOGI(TAG, "LVGL display driver initialized");
 }
+
+void display_set_target_fps(uint8_t fps)
+{
+    if (disp == NULL) {
+        return;
+    }
+    if (fps < 1) fps = 1;
+    if (fps > 100) fps = 100;
+    if (fps == target_fps) {
+        return;
+    }
+    
+    // LVGL renders invalidated areas at most once per refresh timer period
+    lv_timer_set_period(_lv_disp_get_refr_timer(disp), 1000 / fps);
+    target_fps = fps;
+    ESP_LOGD(TAG, "Target frame rate %u FPS", fps);
+}
+
+uint8_t display_get_target_fps(void)
+{
+    return target_fps;
+}
+
+void display_mark_for_redraw(void)
+{
+    // Invalidate the whole active screen so the next refresh repaints every pixel
+    lv_obj_invalidate(lv_scr_act());
+}
+
+bool display_framebuffer_in_psram(void)
+{
+    return draw_buf_in_psram;
+}
+
+bool display_is_double_buffered(void)
+{
+    return double_buffered;
+}
+
+void display_set_rotation(display_rotation_t rotation)
+{
+    if (disp == NULL) {
+        ESP_LOGW(TAG, "Display driver not initialized, rotation ignored");
+        return;
+    }
+    
+    size_t buffer_size = (size_t)display_config.h_res * display_config.v_res;
+    
+    if (rotation != DISPLAY_ROTATION_0) {
+        // flush_cb writes a rotated copy into the scanned-out framebuffer, so
+        // LVGL must render into buf1 (never a panel framebuffer) and only
+        // flush what changed
+        bool in_psram;
+        if (buf1 == NULL) {
+            buf1 = display_alloc_buffer(buffer_size * sizeof(lv_color_t), &in_psram);
+        }
+        if (rot_buf == NULL) {
+            rot_buf = display_alloc_buffer(buffer_size * sizeof(uint16_t), &in_psram);
+        }
+        if (buf1 == NULL || rot_buf == NULL) {
+            ESP_LOGE(TAG, "No memory for the rotation buffers, rotation ignored");
+            return;
+        }
+        if (disp_drv.full_refresh) {
+            lv_disp_draw_buf_init(&draw_buf, buf1, NULL, buffer_size);
+            disp_drv.full_refresh = 0;
+        }
+    } else if (double_buffered && !disp_drv.full_refresh) {
+        // Back to rendering straight into the panel framebuffers, starting
+        // with the one that isn't on screen
+        void *fb0 = NULL;
+        void *fb1 = NULL;
+        ESP_ERROR_CHECK(esp_lcd_rgb_panel_get_frame_buffer(panel_handle, 2, &fb0, &fb1));
+        if (front_fb == fb0) {
+            lv_disp_draw_buf_init(&draw_buf, fb1, fb0, buffer_size);
+        } else {
+            lv_disp_draw_buf_init(&draw_buf, fb0, fb1, buffer_size);
+        }
+        disp_drv.full_refresh = 1;
+    }
+    
+    // LVGL swaps the effective horizontal/vertical resolution for 90/270,
+    // picks up the draw buffer change and maps touch coordinates into the
+    // rotated space in its indev layer
+    lv_disp_set_rotation(disp, (lv_disp_rot_t)rotation);
+    display_mark_for_redraw();
+    ESP_LOGI(TAG, "Display rotation set to %d degrees", rotation * 90);
+}
+
+display_rotation_t display_get_rotation(void)
+{
+    if (disp == NULL) {
+        return DISPLAY_ROTATION_0;
+    }
+    return (display_rotation_t)lv_disp_get_rotation(disp);
+}
+
+const uint16_t *display_get_framebuffer(void)
+{
+    return (const uint16_t *)front_fb;
+}
+
+bool display_get_pixel(uint16_t x, uint16_t y, uint16_t *color)
+{
+    if (front_fb == NULL || color == NULL || x >= display_config.h_res || y >= display_config.v_res) {
+        return false;
+    }
+    
+    *color = ((const uint16_t *)front_fb)[(uint32_t)y * display_config.h_res + x];
+    return true;
+}
+
+uint16_t display_get_width(void)
+{
+    return display_config.h_res;
+}
+
+uint16_t display_get_height(void)
+{
+    return display_config.v_res;
+}
+
+#if CONFIG_DISPLAY_FB_CAPTURE
+// 768 raw bytes encode to exactly 1024 base64 characters per log line
+#define FB_CAPTURE_CHUNK_BYTES 768
+
+void display_capture_framebuffer(void)
+{
+    const uint8_t *fb = (const uint8_t *)display_get_framebuffer();
+    if (fb == NULL) {
+        ESP_LOGE(TAG, "Framebuffer capture failed: no framebuffer");
+        return;
+    }
+    
+    size_t total = (size_t)display_config.h_res * display_config.v_res * sizeof(uint16_t);
+    unsigned char line[((FB_CAPTURE_CHUNK_BYTES + 2) / 3) * 4 + 1];
+    
+    printf("FBCAP BEGIN %u %u RGB565LE\n", display_config.h_res, display_config.v_res);
+    for (size_t offset = 0; offset < total; offset += FB_CAPTURE_CHUNK_BYTES) {
+        size_t len = total - offset;
+        if (len > FB_CAPTURE_CHUNK_BYTES) {
+            len = FB_CAPTURE_CHUNK_BYTES;
+        }
+        
+        size_t written = 0;
+        if (mbedtls_base64_encode(line, sizeof(line), &written, fb + offset, len) != 0) {
+            ESP_LOGE(TAG, "Framebuffer capture failed at offset %u", (unsigned)offset);
+            break;
+        }
+        printf("FBCAP %s\n", line);
+        
+        // Let the UART drain and keep the watchdog fed
+        if ((offset / FB_CAPTURE_CHUNK_BYTES) % 16 == 15) {
+            vTaskDelay(1);
+        }
+    }
+    printf("FBCAP END\n");
+}
+#endif
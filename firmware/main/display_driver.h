@@ -2,15 +2,149 @@
 #define DISPLAY_DRIVER_H
 
 #include <stdint.h>
+#include <stdbool.h>
+#include "sdkconfig.h"
 #include "lvgl.h"
+#include "display_rotation.h"
 
+// Default panel resolution (the D1's 480x480 ST7701S); the active size comes
+// from display_config_t and is read back with display_get_width/height()
 #define DISP_HOR_RES 480
 #define DISP_VER_RES 480
 
-// Display initialization
-void display_init(void);
+// ST7701S gamma curves: 16 bytes each, sent to 0xB0 (positive) and 0xB1 (negative)
+#define DISPLAY_GAMMA_LEN 16
+
+typedef struct {
+    uint8_t positive[DISPLAY_GAMMA_LEN];
+    uint8_t negative[DISPLAY_GAMMA_LEN];
+} display_gamma_t;
+
+// Gamma values from the Seeed reference init sequence
+#define DISPLAY_GAMMA_DEFAULT() {                                       \
+    .positive = { 0x00, 0x11, 0x18, 0x0E, 0x11, 0x06, 0x07, 0x08,       \
+                  0x07, 0x22, 0x04, 0x12, 0x0F, 0xAA, 0x31, 0x18 },     \
+    .negative = { 0x00, 0x11, 0x19, 0x0E, 0x12, 0x07, 0x08, 0x08,       \
+                  0x08, 0x22, 0x04, 0x11, 0x11, 0xA9, 0x32, 0x18 },     \
+}
+
+// RGB interface timing
+typedef struct {
+    uint32_t pclk_hz;
+    uint16_t hsync_back_porch;
+    uint16_t hsync_front_porch;
+    uint16_t hsync_pulse_width;
+    uint16_t vsync_back_porch;
+    uint16_t vsync_front_porch;
+    uint16_t vsync_pulse_width;
+} display_timing_t;
+
+// 480x480 ST7701S timing from the Seeed board support (sensecap_indicator_board.c)
+#define DISPLAY_TIMING_DEFAULT() {      \
+    .pclk_hz = 16000000,                \
+    .hsync_back_porch = 50,             \
+    .hsync_front_porch = 10,            \
+    .hsync_pulse_width = 8,             \
+    .vsync_back_porch = 50,             \
+    .vsync_front_porch = 10,            \
+    .vsync_pulse_width = 8,             \
+}
+
+// Half-period of the bit-banged 9-bit SPI clock used for the ST7701S init sequence
+#define DISPLAY_SPI_DELAY_US_DEFAULT 10
+
+// Panel configuration passed to display_init()
+typedef struct {
+    uint16_t h_res;
+    uint16_t v_res;
+    display_timing_t timing;
+    display_gamma_t gamma;
+    uint8_t spi_delay_us;   // Lower values shorten cold boot; the ST7701S accepts a few us
+} display_config_t;
+
+#define DISPLAY_CONFIG_DEFAULT() {                      \
+    .h_res = DISP_HOR_RES,                              \
+    .v_res = DISP_VER_RES,                              \
+    .timing = DISPLAY_TIMING_DEFAULT(),                 \
+    .gamma = DISPLAY_GAMMA_DEFAULT(),                   \
+    .spi_delay_us = DISPLAY_SPI_DELAY_US_DEFAULT,       \
+}
+
+// Display initialization (config may be NULL for DISPLAY_CONFIG_DEFAULT())
+void display_init(const display_config_t *config);
 void display_driver_init(void);
 
+// Backlight brightness (0-100%, values above 100 are clamped)
+void display_set_backlight(uint8_t percent);
+uint8_t display_get_backlight(void);
+
+// Default ramp for backlight transitions (sleep, wake, dimming)
+#define DISPLAY_BACKLIGHT_FADE_MS 300
+
+// Ramp the backlight to percent over duration_ms using the LEDC hardware fade.
+// With wait false it returns immediately; display_get_backlight() reports the
+// target level right away. A later set or fade replaces a running one.
+void display_fade_backlight(uint8_t percent, uint32_t duration_ms, bool wait);
+
+// Panel color inversion (ST7701S INVON/INVOFF), callable any time after display_init
+void display_set_inversion(bool on);
+bool display_get_inversion(void);
+
+// ST7701S Memory Access Control (0x36): the init value and the bits
+// display_set_mirror() adds to it
+#define DISPLAY_MADCTL_DEFAULT   0x10
+#define DISPLAY_MADCTL_MIRROR_X  0x40
+#define DISPLAY_MADCTL_MIRROR_Y  0x80
+
+// Mirror the panel scan for enclosures that mount the board flipped (both
+// axes = 180 degrees) without the per-flush copy display_set_rotation()
+// needs. Touch coordinates are mirrored to match. Callable before
+// display_init (applied during init) or any time after it.
+void display_set_mirror(bool mirror_x, bool mirror_y);
+void display_get_mirror(bool *mirror_x, bool *mirror_y);
+
+// Panel power saving: sleep turns the backlight off and puts the ST7701S in
+// Sleep In; wake restores it and the previous backlight level
+void display_sleep(void);
+void display_wake(void);
+bool display_is_asleep(void);
+
+// Rotate display output and touch input (call after display_driver_init, from
+// the LVGL thread). Any rotation other than 0 renders through a separate
+// buffer with partial refresh, even when double buffering is enabled.
+void display_set_rotation(display_rotation_t rotation);
+display_rotation_t display_get_rotation(void);
+
+// Cap the LVGL refresh rate (1-100 FPS); starts at CONFIG_DISPLAY_ACTIVE_FPS
+void display_set_target_fps(uint8_t fps);
+uint8_t display_get_target_fps(void);
+
+// Force a full-screen repaint on the next LVGL refresh
+void display_mark_for_redraw(void);
+
+// True when the panel was created with two framebuffers (tear-free swapping)
+bool display_is_double_buffered(void);
+
+// True when the LVGL draw buffer lives in PSRAM; false means it fell back to
+// internal RAM, which leaves little heap for WiFi/MQTT
+bool display_framebuffer_in_psram(void);
+
+// Active resolution from the display_init() config
+uint16_t display_get_width(void);
+uint16_t display_get_height(void);
+
+// Framebuffer currently shown on the panel (width x height RGB565)
+const uint16_t *display_get_framebuffer(void);
+
+// Read one RGB565 pixel of the visible framebuffer (panel coordinates);
+// false when out of range or before display_driver_init
+bool display_get_pixel(uint16_t x, uint16_t y, uint16_t *color);
+
+#if CONFIG_DISPLAY_FB_CAPTURE
+// Dump the visible framebuffer over the serial console as base64 (see Kconfig help)
+void display_capture_framebuffer(void);
+#endif
+
 // LVGL flush callback
 void display_flush_cb(lv_disp_drv_t *drv, const lv_area_t *area, lv_color_t *color_map);
 
@@ -0,0 +1,83 @@
+#include "display_rotation.h"
+
+static inline void map_point(display_rotation_t rotation, uint16_t panel_w, uint16_t panel_h,
+                             int16_t x, int16_t y, int16_t *px, int16_t *py)
+{
+    switch (rotation) {
+        case DISPLAY_ROTATION_90:
+            *px = y;
+            *py = (int16_t)(panel_h - 1 - x);
+            break;
+        case DISPLAY_ROTATION_180:
+            *px = (int16_t)(panel_w - 1 - x);
+            *py = (int16_t)(panel_h - 1 - y);
+            break;
+        case DISPLAY_ROTATION_270:
+            *px = (int16_t)(panel_w - 1 - y);
+            *py = x;
+            break;
+        default:
+            *px = x;
+            *py = y;
+            break;
+    }
+}
+
+void display_rotation_map_point(display_rotation_t rotation, uint16_t panel_w, uint16_t panel_h,
+                                int16_t x, int16_t y, int16_t *px, int16_t *py)
+{
+    map_point(rotation, panel_w, panel_h, x, y, px, py);
+}
+
+void display_rotation_unmap_point(display_rotation_t rotation, uint16_t panel_w, uint16_t panel_h,
+                                  int16_t px, int16_t py, int16_t *x, int16_t *y)
+{
+    switch (rotation) {
+        case DISPLAY_ROTATION_90:
+            *x = (int16_t)(panel_h - 1 - py);
+            *y = px;
+            break;
+        case DISPLAY_ROTATION_180:
+            *x = (int16_t)(panel_w - 1 - px);
+            *y = (int16_t)(panel_h - 1 - py);
+            break;
+        case DISPLAY_ROTATION_270:
+            *x = py;
+            *y = (int16_t)(panel_w - 1 - px);
+            break;
+        default:
+            *x = px;
+            *y = py;
+            break;
+    }
+}
+
+void display_rotation_map_area(display_rotation_t rotation, uint16_t panel_w, uint16_t panel_h,
+                               const display_area_t *area, display_area_t *out)
+{
+    int16_t ax, ay, bx, by;
+    map_point(rotation, panel_w, panel_h, area->x1, area->y1, &ax, &ay);
+    map_point(rotation, panel_w, panel_h, area->x2, area->y2, &bx, &by);
+    
+    // Opposite corners stay opposite, but may swap sides
+    out->x1 = ax < bx ? ax : bx;
+    out->x2 = ax < bx ? bx : ax;
+    out->y1 = ay < by ? ay : by;
+    out->y2 = ay < by ? by : ay;
+}
+
+void display_rotation_copy(display_rotation_t rotation, uint16_t panel_w, uint16_t panel_h,
+                           const display_area_t *area, const uint16_t *src, uint16_t *dst)
+{
+    display_area_t out;
+    display_rotation_map_area(rotation, panel_w, panel_h, area, &out);
+    int32_t out_w = out.x2 - out.x1 + 1;
+    
+    for (int16_t y = area->y1; y <= area->y2; y++) {
+        for (int16_t x = area->x1; x <= area->x2; x++) {
+            int16_t px, py;
+            map_point(rotation, panel_w, panel_h, x, y, &px, &py);
+            dst[(int32_t)(py - out.y1) * out_w + (px - out.x1)] = *src++;
+        }
+    }
+}
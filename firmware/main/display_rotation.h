@@ -0,0 +1,47 @@
+#ifndef DISPLAY_ROTATION_H
+#define DISPLAY_ROTATION_H
+
+#include <stdint.h>
+
+// Pixel mapping for rotated output. "Logical" coordinates are what LVGL
+// renders in its rotated frame; "panel" coordinates address the unrotated
+// panel_w x panel_h framebuffer. No LVGL or ESP-IDF dependencies, so the
+// host tests build it as is.
+
+// Screen rotation (values match lv_disp_rot_t)
+typedef enum {
+    DISPLAY_ROTATION_0 = 0,
+    DISPLAY_ROTATION_90 = 1,
+    DISPLAY_ROTATION_180 = 2,
+    DISPLAY_ROTATION_270 = 3
+} display_rotation_t;
+
+// Inclusive rectangle, laid out like lv_area_t
+typedef struct {
+    int16_t x1;
+    int16_t y1;
+    int16_t x2;
+    int16_t y2;
+} display_area_t;
+
+// Map a logical pixel to the panel. This is the inverse of the mapping LVGL
+// applies to touch points in its indev layer, so drawing and touch agree.
+void display_rotation_map_point(display_rotation_t rotation, uint16_t panel_w, uint16_t panel_h,
+                                int16_t x, int16_t y, int16_t *px, int16_t *py);
+
+// Map a panel point (e.g. a touch) to logical coordinates, the same
+// transform LVGL's indev layer applies to pointer input
+void display_rotation_unmap_point(display_rotation_t rotation, uint16_t panel_w, uint16_t panel_h,
+                                  int16_t px, int16_t py, int16_t *x, int16_t *y);
+
+// Map a logical area to the panel rectangle it lands on
+void display_rotation_map_area(display_rotation_t rotation, uint16_t panel_w, uint16_t panel_h,
+                               const display_area_t *area, display_area_t *out);
+
+// Copy a rendered logical area (row-major RGB565) into dst, laid out row-major
+// as the panel rectangle from display_rotation_map_area(). dst must hold as
+// many pixels as src and must not overlap it.
+void display_rotation_copy(display_rotation_t rotation, uint16_t panel_w, uint16_t panel_h,
+                           const display_area_t *area, const uint16_t *src, uint16_t *dst);
+
+#endif // DISPLAY_ROTATION_H
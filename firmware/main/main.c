@@ -10,111 +10,105 @@
 #include <stdlib.h>
 #include "freertos/FreeRTOS.h"
 #include "freertos/task.h"
-#include "freertos/event_groups.h"
 #include "esp_system.h"
 #include "esp_log.h"
 #include "nvs_flash.h"
 #include "esp_wifi.h"
 #include "esp_event.h"
-#include "mqtt_client.h"
+#include "esp_timer.h"
+#include "esp_heap_caps.h"
 
 #include "lvgl.h"
 #include "ui.h"
 #include "display_driver.h"
 #include "touch_driver.h"
 #include "wifi_manager.h"
+#include "mqtt_manager.h"
+#include "app_config.h"
 #include "backend.h"
+#include "diagnostics.h"
+#include "selftest.h"
+#include "splash.h"
 
 static const char *TAG = "SENSECAP_FW";
 
-// Event group for WiFi and MQTT status
-#define WIFI_CONNECTED_BIT  BIT0
-#define MQTT_CONNECTED_BIT   BIT1
-static EventGroupHandle_t s_network_event_group;
+// Screen timeout state (CONFIG_SCREEN_DIM_TIMEOUT_MS / CONFIG_SCREEN_SLEEP_TIMEOUT_MS)
+#define SCREEN_TIMEOUT_CHECK_MS  250
+static bool s_screen_dimmed = false;
+static uint8_t s_backlight_before_dim = 100;
 
-// UI update callbacks from backend
-void ui_update_water_level_async(int level);
-void ui_set_bright_state(int state);
-void ui_set_relax_state(int state);
+// Dim, then sleep the display after inactivity. LVGL tracks the last touch;
+// waking from sleep is handled by the touch driver.
+static void screen_timeout_cb(lv_timer_t *timer)
+{
+    uint32_t inactive_ms = lv_disp_get_inactive_time(NULL);
+    
+    // Render less often while nobody is touching the screen
+    display_set_target_fps(inactive_ms >= CONFIG_DISPLAY_IDLE_FPS_DELAY_MS ?
+                           CONFIG_DISPLAY_IDLE_FPS : CONFIG_DISPLAY_ACTIVE_FPS);
+    
+    if (CONFIG_SCREEN_SLEEP_TIMEOUT_MS > 0 && inactive_ms >= CONFIG_SCREEN_SLEEP_TIMEOUT_MS) {
+        if (!display_is_asleep()) {
+            // Fades out from the dimmed level; s_screen_dimmed stays set so
+            // the first tick after waking ramps back to the user's level
+            display_sleep();
+        }
+    } else if (CONFIG_SCREEN_DIM_TIMEOUT_MS > 0 && inactive_ms >= CONFIG_SCREEN_DIM_TIMEOUT_MS) {
+        if (!s_screen_dimmed && !display_is_asleep()) {
+            s_backlight_before_dim = display_get_backlight();
+            display_fade_backlight(CONFIG_SCREEN_DIM_PERCENT, DISPLAY_BACKLIGHT_FADE_MS, false);
+            s_screen_dimmed = true;
+        }
+    } else if (s_screen_dimmed) {
+        display_fade_backlight(s_backlight_before_dim, DISPLAY_BACKLIGHT_FADE_MS, false);
+        s_screen_dimmed = false;
+    }
+}
 
-// MQTT topics
-#define MQTT_TOPIC_LIGHT_STATE "sensecap/indicator/light/state"
-#define MQTT_TOPIC_WATER_LEVEL "sensecap/indicator/water/level"
+// Latest WiFi RSSI in dBm, refreshed by network_status_task (0 when not connected)
+static volatile int8_t s_wifi_rssi = 0;
 
-static esp_mqtt_client_handle_t mqtt_client = NULL;
+// Refresh the WiFi status icon (MQTT pushes its state, see mqtt_state_cb)
+#define STATUS_ICON_POLL_MS  1000
 
-// MQTT event handler
-static void mqtt_event_handler(void *handler_args, esp_event_base_t base, int32_t event_id, void *event_data)
+static void status_icon_cb(lv_timer_t *timer)
 {
-    esp_mqtt_event_handle_t event = event_data;
-    
-    switch ((esp_mqtt_event_id_t)event_id) {
-        case MQTT_EVENT_CONNECTED:
-            ESP_LOGI(TAG, "MQTT connected");
-            xEventGroupSetBits(s_network_event_group, MQTT_CONNECTED_BIT);
-            // Subscribe to water level topic
-            esp_mqtt_client_subscribe(mqtt_client, MQTT_TOPIC_WATER_LEVEL, 1);
-            break;
-            
-        case MQTT_EVENT_DISCONNECTED:
-            ESP_LOGI(TAG, "MQTT disconnected");
-            xEventGroupClearBits(s_network_event_group, MQTT_CONNECTED_BIT);
-            break;
-            
-        case MQTT_EVENT_DATA:
-            ESP_LOGI(TAG, "MQTT data received: topic=%.*s, data=%.*s", 
-                     event->topic_len, event->topic, 
-                     event->data_len, event->data);
-            
-            // Handle water level updates
-            if (strncmp(event->topic, MQTT_TOPIC_WATER_LEVEL, event->topic_len) == 0) {
-                char data_str[16];
-                int len = event->data_len < 15 ? event->data_len : 15;
-                memcpy(data_str, event->data, len);
-                data_str[len] = '\0';
-                int water_level = atoi(data_str);
-                ui_update_water_level_async(water_level);
-            }
-            break;
-            
-        case MQTT_EVENT_ERROR:
-            ESP_LOGE(TAG, "MQTT error occurred");
-            break;
-            
-        default:
-            break;
+    bool wifi_connected = wifi_is_connected();
+    ui_set_wifi_connected(wifi_connected);
+    if (wifi_connected && s_wifi_rssi != 0) {
+        ui_set_wifi_rssi(s_wifi_rssi);
     }
 }
 
-// Initialize MQTT client
-static void mqtt_init(void)
+// MQTT connect/disconnect, on the MQTT task
+static void mqtt_state_cb(bool connected)
 {
-    esp_mqtt_client_config_t mqtt_cfg = {
-        .broker.address.uri = CONFIG_MQTT_BROKER_URL,
-        .credentials.client_id = "sensecap_indicator_d1",
-        .session.keepalive = 60,
-    };
-    
-    // Add authentication if username is configured
-    if (strlen(CONFIG_MQTT_USERNAME) > 0) {
-        mqtt_cfg.credentials.username = CONFIG_MQTT_USERNAME;
-        mqtt_cfg.credentials.authentication.password = CONFIG_MQTT_PASSWORD;
-        ESP_LOGI(TAG, "MQTT using authentication with username: %s", CONFIG_MQTT_USERNAME);
-    }
-    
-    mqtt_client = esp_mqtt_client_init(&mqtt_cfg);
-    esp_mqtt_client_register_event(mqtt_client, ESP_EVENT_ANY_ID, mqtt_event_handler, NULL);
-    esp_mqtt_client_start(mqtt_client);
+    ui_set_mqtt_connected_async(connected);
 }
 
-// Publish light state to MQTT
-void publish_light_state(const char* mode, int state)
+// The backend only alerts on a real band change (with hysteresis); this also
+// keeps a tank that is refilled and drained again from re-popping the banner
+#define WATER_TOAST_MIN_INTERVAL_US  (10LL * 60 * 1000 * 1000)
+static int64_t s_last_water_toast_us = 0;
+
+// Backend water alert: pop a banner when the tank goes critical
+static void water_alert_cb(water_band_t band, uint8_t level)
 {
-    if (mqtt_client == NULL) return;
+    ESP_LOGW(TAG, "Water level %u%% entered %s band", level,
+             band == WATER_BAND_CRITICAL ? "critical" : "low");
+    if (band != WATER_BAND_CRITICAL) {
+        return;
+    }
     
-    char payload[64];
-    snprintf(payload, sizeof(payload), "{\"mode\":\"%s\",\"state\":%d}", mode, state);
-    esp_mqtt_client_publish(mqtt_client, MQTT_TOPIC_LIGHT_STATE, payload, 0, 1, 0);
+    int64_t now = esp_timer_get_time();
+    if (s_last_water_toast_us != 0 && now - s_last_water_toast_us < WATER_TOAST_MIN_INTERVAL_US) {
+        ESP_LOGI(TAG, "Water critical banner shown recently, not repeating it");
+        return;
+    }
+    s_last_water_toast_us = now;
+    
+    // May run on the MQTT task, so hand off to the LVGL thread
+    ui_show_water_critical_async();
 }
 
 // LVGL task - handles rendering
@@ -132,20 +126,41 @@ static void lvgl_task(void *pvParameter)
     }
 }
 
-// Network status task
+// Network status task, also checks the heap and sends the MQTT heartbeat
+// (CONFIG_MQTT_HEARTBEAT_INTERVAL_S)
+#define NETWORK_STATUS_POLL_MS  5000
+
 static void network_status_task(void *pvParameter)
 {
+    int64_t last_heartbeat_us = 0;
+    
     while (1) {
-        EventBits_t bits = xEventGroupGetBits(s_network_event_group);
+        bool wifi_connected = wifi_is_connected();
+        bool mqtt_connected = mqtt_is_connected();
         
-        bool wifi_connected = (bits & WIFI_CONNECTED_BIT) != 0;
-        bool mqtt_connected = (bits & MQTT_CONNECTED_BIT) != 0;
+        int8_t rssi;
+        s_wifi_rssi = wifi_get_rssi(&rssi) ? rssi : 0;
         
-        ESP_LOGD(TAG, "Network status: WiFi=%s, MQTT=%s",
+        ESP_LOGD(TAG, "Network status: WiFi=%s (RSSI %d dBm), MQTT=%s",
                  wifi_connected ? "connected" : "disconnected",
+                 s_wifi_rssi,
                  mqtt_connected ? "connected" : "disconnected");
         
-        vTaskDelay(pdMS_TO_TICKS(5000));
+        diagnostics_memory_t mem;
+        diagnostics_check_memory(&mem);
+        
+        int64_t now_us = esp_timer_get_time();
+        if (CONFIG_MQTT_HEARTBEAT_INTERVAL_S > 0 && mqtt_connected &&
+            now_us - last_heartbeat_us >= (int64_t)CONFIG_MQTT_HEARTBEAT_INTERVAL_S * 1000000) {
+            last_heartbeat_us = now_us;
+            mqtt_publish_status(diagnostics_get_uptime_s(),
+                                (uint32_t)heap_caps_get_free_size(MALLOC_CAP_DEFAULT),
+                                mem.free_internal,
+                                s_wifi_rssi,
+                                diagnostics_reset_reason_str(diagnostics_get_reset_reason()));
+        }
+        
+        vTaskDelay(pdMS_TO_TICKS(NETWORK_STATUS_POLL_MS));
     }
 }
 
@@ -165,20 +180,24 @@ void app_main(void)
     ESP_LOGI(TAG, "======================================");
     ESP_LOGI(TAG, "SenseCAP Indicator D1 Firmware v1.0");
     ESP_LOGI(TAG, "======================================");
+    diagnostics_init();
     
     // Initialize NVS
     ESP_ERROR_CHECK(nvs_init());
     
-    // Initialize network event group
-    s_network_event_group = xEventGroupCreate();
+    // Load WiFi/MQTT settings (NVS first, Kconfig defaults otherwise)
+    static app_config_t app_config;
+    config_load(&app_config);
     
     // Initialize display
     ESP_LOGI(TAG, "Initializing display...");
-    display_init();
+    display_init(NULL);
     
     // Initialize touch
     ESP_LOGI(TAG, "Initializing touch...");
     touch_init();
+    // Checked before anything else touches the controller, while the finger is still down
+    bool run_selftest = selftest_requested();
     
     // Initialize LVGL
     ESP_LOGI(TAG, "Initializing LVGL...");
@@ -186,40 +205,67 @@ void app_main(void)
     
     // Initialize display driver for LVGL
     display_driver_init();
+    if (!display_framebuffer_in_psram()) {
+        ESP_LOGW(TAG, "Display buffer is in internal RAM; later allocations may fail");
+    }
     
     // Initialize touch driver for LVGL
     touch_driver_init();
     
+    if (run_selftest) {
+        // Manufacturing self-test replaces the UI, network and backend until reset
+        selftest_start();
+        xTaskCreatePinnedToCore(lvgl_task, "lvgl_task", 4096, NULL, 5, NULL, 1);
+        vTaskDelete(NULL);
+    }
+    
+    // Boot progress on screen until the main UI takes over
+    splash_show();
+    
     // Initialize UI
     ESP_LOGI(TAG, "Initializing UI...");
+    splash_progress(20, "Loading UI...");
     ui_init();
+    lv_timer_create(screen_timeout_cb, SCREEN_TIMEOUT_CHECK_MS, NULL);
+    lv_timer_create(status_icon_cb, STATUS_ICON_POLL_MS, NULL);
+    ui_set_mqtt_connected(false);
     
     // Initialize WiFi
     ESP_LOGI(TAG, "Initializing WiFi...");
+    splash_progress(40, "Connecting to WiFi...");
     wifi_init();
-    wifi_connect(CONFIG_WIFI_SSID, CONFIG_WIFI_PASSWORD);
+    // Waits for the first connection; if it fails, WiFi keeps retrying in
+    // the background and MQTT connects once the network is up
+    wifi_connect(app_config.wifi_ssid, app_config.wifi_password);
+    ESP_LOGI(TAG, "WiFi %s", wifi_is_connected() ? "connected!" : "not connected yet, continuing");
     
-    // Wait for WiFi connection
-    ESP_LOGI(TAG, "Waiting for WiFi connection...");
-    xEventGroupWaitBits(s_network_event_group, WIFI_CONNECTED_BIT, pdFALSE, pdTRUE, portMAX_DELAY);
-    ESP_LOGI(TAG, "WiFi connected!");
+    // Initialize backend before MQTT: a retained water level can arrive as
+    // soon as MQTT connects and must not be overwritten by the NVS restore
+    ESP_LOGI(TAG, "Initializing backend...");
+    splash_progress(60, "Restoring state...");
+    backend_register_water_alert_callback(water_alert_cb);
+    backend_set_tank_capacity(app_config.tank_capacity_l);
+    backend_init();
     
     // Initialize MQTT
     ESP_LOGI(TAG, "Initializing MQTT...");
-    mqtt_init();
-    
-    // Initialize backend
-    ESP_LOGI(TAG, "Initializing backend...");
-    backend_init();
+    splash_progress(80, "Connecting to MQTT...");
+    mqtt_register_state_callback(mqtt_state_cb);
+    esp_err_t mqtt_err = mqtt_init(&app_config);
+    if (mqtt_err != ESP_OK) {
+        ESP_LOGE(TAG, "MQTT init failed: %s", esp_err_to_name(mqtt_err));
+    }
+    splash_progress(100, "Ready");
+    splash_finish(ui_Screen_1);
     
     // Create tasks
     ESP_LOGI(TAG, "Creating tasks...");
     xTaskCreatePinnedToCore(lvgl_task, "lvgl_task", 4096, NULL, 5, NULL, 1);
-    xTaskCreatePinnedToCore(network_status_task, "network_status", 2048, NULL, 3, NULL, 0);
+    xTaskCreatePinnedToCore(network_status_task, "network_status", 3072, NULL, 3, NULL, 0);
     
     ESP_LOGI(TAG, "Setup complete!");
-    ESP_LOGI(TAG, "Display: 480x480, Touch: enabled");
-    ESP_LOGI(TAG, "MQTT broker: %s", CONFIG_MQTT_BROKER_URL);
+    ESP_LOGI(TAG, "Display: %ux%u, Touch: enabled", display_get_width(), display_get_height());
+    ESP_LOGI(TAG, "MQTT broker: %s", app_config.mqtt_broker_url);
     
     // Main task can now exit, other tasks handle the work
     vTaskDelete(NULL);
@@ -0,0 +1,633 @@
+#include "mqtt_manager.h"
+#include <stdio.h>
+#include <string.h>
+#include <stdlib.h>
+#include <ctype.h>
+#include <math.h>
+#include <stdatomic.h>
+#include "esp_log.h"
+#include "esp_timer.h"
+#include "esp_mac.h"
+#include "freertos/FreeRTOS.h"
+#include "freertos/task.h"
+#include "mqtt_client.h"
+#include "cJSON.h"
+#include "backend.h"
+
+static const char *TAG = "MQTT";
+
+#if CONFIG_MQTT_USE_CA_CERT
+// Embedded from main/certs/mqtt_ca.pem (see CMakeLists.txt)
+extern const char mqtt_ca_pem_start[] asm("_binary_mqtt_ca_pem_start");
+#endif
+
+static esp_mqtt_client_handle_t mqtt_client = NULL;
+static char client_id[MQTT_CLIENT_ID_MAX_LEN];
+
+// Full topics built from the configured prefix in mqtt_init()
+static char topic_light_state[MQTT_TOPIC_MAX_LEN];
+static char topic_light_command[MQTT_TOPIC_MAX_LEN];
+static char topic_water_level[MQTT_TOPIC_MAX_LEN];
+static char topic_status[MQTT_TOPIC_MAX_LEN];
+static char topic_telemetry[MQTT_TOPIC_MAX_LEN];
+static char topic_device_state[MQTT_TOPIC_MAX_LEN];
+static char topic_water_get[MQTT_TOPIC_MAX_LEN];
+static esp_timer_handle_t reconnect_timer = NULL;
+static esp_timer_handle_t water_bootstrap_timer = NULL;
+static volatile bool water_level_received = false;
+static volatile bool mqtt_connected = false;
+static mqtt_state_cb_t volatile state_cb = NULL;
+static uint32_t reconnect_attempt = 0;
+static uint32_t reconnect_count = 0;
+
+// Sequence number stamped into every JSON payload we publish; never reset
+// by reconnects, so gaps on the dashboard side mean dropped messages
+static atomic_uint publish_seq = 0;
+
+// Bounded FIFO of publishes made while offline (oldest dropped when full)
+typedef struct {
+    const char *topic;
+    char *payload;
+    int qos;
+    int retain;
+} pending_publish_t;
+
+static pending_publish_t pending[MQTT_PENDING_QUEUE_LEN];
+static uint8_t pending_head = 0;
+static uint8_t pending_count = 0;
+static portMUX_TYPE pending_lock = portMUX_INITIALIZER_UNLOCKED;
+
+// Queue a publish; takes ownership of payload
+static void pending_push(const char *topic, char *payload, int qos, int retain)
+{
+    char *dropped = NULL;
+    
+    taskENTER_CRITICAL(&pending_lock);
+    if (pending_count == MQTT_PENDING_QUEUE_LEN) {
+        dropped = pending[pending_head].payload;
+        pending_head = (pending_head + 1) % MQTT_PENDING_QUEUE_LEN;
+        pending_count--;
+    }
+    uint8_t tail = (pending_head + pending_count) % MQTT_PENDING_QUEUE_LEN;
+    pending[tail] = (pending_publish_t){ .topic = topic, .payload = payload, .qos = qos, .retain = retain };
+    pending_count++;
+    taskEXIT_CRITICAL(&pending_lock);
+    
+    if (dropped != NULL) {
+        ESP_LOGW(TAG, "Pending publish queue full, dropped oldest: %s", dropped);
+        cJSON_free(dropped);
+    }
+}
+
+// Send everything queued while offline, oldest first
+static void pending_flush(void)
+{
+    pending_publish_t item;
+    
+    for (;;) {
+        taskENTER_CRITICAL(&pending_lock);
+        if (pending_count == 0) {
+            taskEXIT_CRITICAL(&pending_lock);
+            break;
+        }
+        item = pending[pending_head];
+        pending_head = (pending_head + 1) % MQTT_PENDING_QUEUE_LEN;
+        pending_count--;
+        taskEXIT_CRITICAL(&pending_lock);
+        
+        ESP_LOGI(TAG, "Flushing queued publish to %s: %s", item.topic, item.payload);
+        esp_mqtt_client_publish(mqtt_client, item.topic, item.payload, 0, item.qos, item.retain);
+        cJSON_free(item.payload);
+    }
+}
+
+static void reconnect_timer_cb(void *arg)
+{
+    ESP_LOGI(TAG, "Reconnecting to MQTT broker (attempt %u)", (unsigned)reconnect_attempt);
+    esp_mqtt_client_reconnect(mqtt_client);
+}
+
+// Schedule the next reconnect attempt with exponential backoff
+static void schedule_reconnect(void)
+{
+    uint32_t delay_ms = MQTT_RECONNECT_MIN_DELAY_MS;
+    for (uint32_t i = 0; i < reconnect_attempt && delay_ms < MQTT_RECONNECT_MAX_DELAY_MS; i++) {
+        delay_ms *= 2;
+    }
+    if (delay_ms > MQTT_RECONNECT_MAX_DELAY_MS) {
+        delay_ms = MQTT_RECONNECT_MAX_DELAY_MS;
+    }
+    reconnect_attempt++;
+
+    ESP_LOGI(TAG, "Retrying MQTT connection in %u ms", (unsigned)delay_ms);
+    esp_timer_stop(reconnect_timer);
+    esp_timer_start_once(reconnect_timer, (uint64_t)delay_ms * 1000);
+}
+
+// Nothing arrived on the water topic since subscribing: ask the sensor node
+static void water_bootstrap_timer_cb(void *arg)
+{
+    if (!mqtt_connected || water_level_received) {
+        return;
+    }
+    ESP_LOGI(TAG, "No water level after subscribe, requesting one on %s", topic_water_get);
+    esp_mqtt_client_publish(mqtt_client, topic_water_get, "", 0, 1, 0);
+}
+
+// Read a water level from a JSON number or numeric string. Floats are
+// truncated and out-of-range values clamped to 0-100; NaN, infinities and
+// non-numeric strings are rejected.
+static bool parse_water_level(const cJSON *item, int *out)
+{
+    double value;
+    
+    if (cJSON_IsNumber(item)) {
+        value = item->valuedouble;
+    } else if (cJSON_IsString(item) && item->valuestring != NULL) {
+        char *end;
+        value = strtod(item->valuestring, &end);
+        while (isspace((unsigned char)*end)) end++;
+        if (end == item->valuestring || *end != '\0') {
+            return false;
+        }
+    } else {
+        return false;
+    }
+    
+    if (!isfinite(value)) {
+        return false;
+    }
+    if (value < 0) value = 0;
+    if (value > 100) value = 100;
+    *out = (int)value;
+    return true;
+}
+
+static void handle_water_level(const char *data, int len)
+{
+    // Reject trailing garbage ("42abc"); surrounding whitespace is fine
+    const char *end = NULL;
+    cJSON *root = cJSON_ParseWithLengthOpts(data, len, &end, false);
+    if (root != NULL) {
+        while (end < data + len && isspace((unsigned char)*end)) end++;
+        if (end != data + len) {
+            cJSON_Delete(root);
+            root = NULL;
+        }
+    }
+    
+    const cJSON *level = cJSON_IsObject(root) ? cJSON_GetObjectItemCaseSensitive(root, "level") : root;
+    int water_level;
+    if (!parse_water_level(level, &water_level)) {
+        ESP_LOGW(TAG, "Ignoring malformed water level: %.*s", len, data);
+        cJSON_Delete(root);
+        return;
+    }
+    cJSON_Delete(root);
+    water_level_received = true;
+    
+    // Received levels are not re-published by the backend
+    backend_update_water_level((uint8_t)water_level);
+}
+
+// Apply a {"mode":"bright|relax","state":0|1[,"level":0-100]} or {"mode":"off"} command;
+// malformed payloads are ignored
+static void handle_light_command(const char *data, int len)
+{
+    cJSON *root = cJSON_ParseWithLength(data, len);
+    if (root == NULL) {
+        ESP_LOGW(TAG, "Ignoring malformed light command: %.*s", len, data);
+        return;
+    }
+    
+    const cJSON *mode = cJSON_GetObjectItemCaseSensitive(root, "mode");
+    const cJSON *state = cJSON_GetObjectItemCaseSensitive(root, "state");
+    const cJSON *level = cJSON_GetObjectItemCaseSensitive(root, "level");
+    
+    // Optional brightness, applied before the switch so it is published with it
+    if (cJSON_IsNumber(level)) {
+        backend_set_brightness(level->valueint < 0 ? 0 : (level->valueint > 100 ? 100 : level->valueint));
+    }
+    
+    if (cJSON_IsString(mode) && strcmp(mode->valuestring, "off") == 0) {
+        backend_lights_all_off();
+    } else if (!cJSON_IsString(mode) || !cJSON_IsNumber(state)) {
+        ESP_LOGW(TAG, "Ignoring light command without mode/state: %.*s", len, data);
+    } else if (!backend_apply_light_command(mode->valuestring, state->valueint ? 1 : 0)) {
+        ESP_LOGW(TAG, "Ignoring light command with unknown mode: %s", mode->valuestring);
+    }
+    
+    cJSON_Delete(root);
+}
+
+#if CONFIG_MQTT_HA_DISCOVERY
+// Fill the fields shared by every discovery config
+static void discovery_add_common(cJSON *cfg, const char *object_id, const char *name)
+{
+    char unique_id[64];
+    snprintf(unique_id, sizeof(unique_id), "%s_%s", client_id, object_id);
+    
+    cJSON_AddStringToObject(cfg, "name", name);
+    cJSON_AddStringToObject(cfg, "unique_id", unique_id);
+    cJSON_AddStringToObject(cfg, "availability_topic", topic_status);
+    cJSON_AddStringToObject(cfg, "payload_available", MQTT_STATUS_ONLINE);
+    cJSON_AddStringToObject(cfg, "payload_not_available", MQTT_STATUS_OFFLINE);
+    
+    cJSON *device = cJSON_AddObjectToObject(cfg, "device");
+    cJSON *ids = cJSON_AddArrayToObject(device, "identifiers");
+    cJSON_AddItemToArray(ids, cJSON_CreateString(client_id));
+    cJSON_AddStringToObject(device, "name", "SenseCAP Indicator D1");
+    cJSON_AddStringToObject(device, "manufacturer", "Seeed Studio");
+    cJSON_AddStringToObject(device, "model", "SenseCAP Indicator D1");
+}
+
+// Publish a retained discovery config to <prefix>/<component>/<client_id>/<object_id>/config
+static void discovery_publish(const char *component, const char *object_id, cJSON *cfg)
+{
+    char topic[128];
+    snprintf(topic, sizeof(topic), "%s/%s/%s/%s/config",
+             MQTT_HA_DISCOVERY_PREFIX, component, client_id, object_id);
+    
+    char *payload = cJSON_PrintUnformatted(cfg);
+    cJSON_Delete(cfg);
+    if (payload == NULL) {
+        ESP_LOGE(TAG, "Failed to serialize discovery config for %s", object_id);
+        return;
+    }
+    esp_mqtt_client_publish(mqtt_client, topic, payload, 0, 1, 1);
+    cJSON_free(payload);
+}
+
+// Light switches are driven through the light/command topic and report on
+// light/state, which carries one mode per message
+static void discovery_publish_switch(const char *mode, const char *name)
+{
+    char buf[96];
+    cJSON *cfg = cJSON_CreateObject();
+    discovery_add_common(cfg, mode, name);
+    
+    cJSON_AddStringToObject(cfg, "command_topic", topic_light_command);
+    snprintf(buf, sizeof(buf), "{\"mode\":\"%s\",\"state\":1}", mode);
+    cJSON_AddStringToObject(cfg, "payload_on", buf);
+    snprintf(buf, sizeof(buf), "{\"mode\":\"%s\",\"state\":0}", mode);
+    cJSON_AddStringToObject(cfg, "payload_off", buf);
+    
+    // The other mode turning on forces this one off (mutual exclusion)
+    cJSON_AddStringToObject(cfg, "state_topic", topic_light_state);
+    snprintf(buf, sizeof(buf), "{{ value_json.state if value_json.mode == '%s' else 0 }}", mode);
+    cJSON_AddStringToObject(cfg, "value_template", buf);
+    cJSON_AddStringToObject(cfg, "state_on", "1");
+    cJSON_AddStringToObject(cfg, "state_off", "0");
+    
+    discovery_publish("switch", mode, cfg);
+}
+
+static void publish_discovery(void)
+{
+    discovery_publish_switch("bright", "Bright Light");
+    discovery_publish_switch("relax", "Relax Light");
+    
+    cJSON *cfg = cJSON_CreateObject();
+    discovery_add_common(cfg, "water_level", "Water Level");
+    cJSON_AddStringToObject(cfg, "state_topic", topic_water_level);
+    cJSON_AddStringToObject(cfg, "value_template", "{{ value_json.level }}");
+    cJSON_AddStringToObject(cfg, "unit_of_measurement", "%");
+    cJSON_AddStringToObject(cfg, "icon", "mdi:water-percent");
+    discovery_publish("sensor", "water_level", cfg);
+    
+    ESP_LOGI(TAG, "Published Home Assistant discovery configs");
+}
+#endif
+
+// Event topics are not NUL-terminated
+static bool topic_matches(esp_mqtt_event_handle_t event, const char *topic)
+{
+    return (size_t)event->topic_len == strlen(topic) &&
+           strncmp(event->topic, topic, event->topic_len) == 0;
+}
+
+// Update the connection flag and tell the registered listener
+static void set_connected(bool connected)
+{
+    mqtt_connected = connected;
+    mqtt_state_cb_t cb = state_cb;
+    if (cb != NULL) {
+        cb(connected);
+    }
+}
+
+// MQTT event handler
+static void mqtt_event_handler(void *handler_args, esp_event_base_t base, int32_t event_id, void *event_data)
+{
+    esp_mqtt_event_handle_t event = event_data;
+    
+    switch ((esp_mqtt_event_id_t)event_id) {
+        case MQTT_EVENT_CONNECTED:
+            ESP_LOGI(TAG, "MQTT connected");
+            if (reconnect_attempt > 0) {
+                reconnect_count++;
+            }
+            reconnect_attempt = 0;
+            set_connected(true);
+            // Announce availability; the broker publishes the last will if we drop
+            esp_mqtt_client_publish(mqtt_client, topic_status, MQTT_STATUS_ONLINE, 0, 1, 1);
+            // Subscribe to water level and light command topics (also after every reconnect).
+            // The broker delivers a retained water level right after the subscribe;
+            // if there is none, the bootstrap timer asks the sensor node for one.
+            water_level_received = false;
+            esp_mqtt_client_subscribe(mqtt_client, topic_water_level, CONFIG_MQTT_WATER_QOS);
+            esp_mqtt_client_subscribe(mqtt_client, topic_light_command, 1);
+            esp_timer_stop(water_bootstrap_timer);
+            esp_timer_start_once(water_bootstrap_timer, (uint64_t)MQTT_WATER_BOOTSTRAP_TIMEOUT_MS * 1000);
+#if CONFIG_MQTT_HA_DISCOVERY
+            publish_discovery();
+#endif
+            pending_flush();
+            publish_device_state();
+            break;
+            
+        case MQTT_EVENT_DISCONNECTED:
+            ESP_LOGI(TAG, "MQTT disconnected");
+            set_connected(false);
+            esp_timer_stop(water_bootstrap_timer);
+            schedule_reconnect();
+            break;
+            
+        case MQTT_EVENT_DATA:
+            ESP_LOGD(TAG, "MQTT data received: topic=%.*s, data=%.*s%s", 
+                     event->topic_len, event->topic, 
+                     event->data_len, event->data,
+                     event->retain ? " (retained)" : "");
+            
+            // Handle water level updates
+            if (topic_matches(event, topic_water_level)) {
+                handle_water_level(event->data, event->data_len);
+            } else if (topic_matches(event, topic_light_command)) {
+                handle_light_command(event->data, event->data_len);
+            }
+            break;
+            
+        case MQTT_EVENT_ERROR:
+            ESP_LOGE(TAG, "MQTT error occurred");
+            break;
+            
+        default:
+            break;
+    }
+}
+
+// Any backend change republishes the full snapshot
+static void device_state_listener(const backend_event_t *event, void *user_data)
+{
+    (void)event;
+    (void)user_data;
+    publish_device_state();
+}
+
+esp_err_t mqtt_init(const app_config_t *config)
+{
+    if (config == NULL || config->mqtt_broker_url[0] == '\0') {
+        return ESP_ERR_INVALID_ARG;
+    }
+    
+    const char *prefix = config->mqtt_topic_prefix;
+    snprintf(topic_light_state, sizeof(topic_light_state), "%s/%s", prefix, MQTT_TOPIC_LIGHT_STATE);
+    snprintf(topic_light_command, sizeof(topic_light_command), "%s/%s", prefix, MQTT_TOPIC_LIGHT_COMMAND);
+    snprintf(topic_water_level, sizeof(topic_water_level), "%s/%s", prefix, MQTT_TOPIC_WATER_LEVEL);
+    snprintf(topic_status, sizeof(topic_status), "%s/%s", prefix, MQTT_TOPIC_STATUS);
+    snprintf(topic_telemetry, sizeof(topic_telemetry), "%s/%s", prefix, MQTT_TOPIC_TELEMETRY);
+    snprintf(topic_device_state, sizeof(topic_device_state), "%s/%s", prefix, MQTT_TOPIC_DEVICE_STATE);
+    snprintf(topic_water_get, sizeof(topic_water_get), "%s/%s", prefix, MQTT_TOPIC_WATER_GET);
+    ESP_LOGI(TAG, "MQTT topic prefix: %s", prefix);
+    
+    // Brokers drop an existing session when another one connects with the
+    // same id, so make it unique per device
+    uint8_t mac[6] = { 0 };
+    if (esp_efuse_mac_get_default(mac) != ESP_OK) {
+        ESP_LOGW(TAG, "Failed to read the eFuse MAC, client ID is not unique");
+    }
+    snprintf(client_id, sizeof(client_id), "%s_%02x%02x%02x",
+             MQTT_CLIENT_ID_BASE, mac[3], mac[4], mac[5]);
+    ESP_LOGI(TAG, "MQTT client ID: %s", client_id);
+    
+    bool use_tls = strncmp(config->mqtt_broker_url, "mqtts://", 8) == 0;
+#if !CONFIG_MQTT_USE_CA_CERT
+    if (use_tls) {
+        ESP_LOGE(TAG, "Broker URL %s uses TLS but no CA certificate is configured; "
+                 "enable MQTT_USE_CA_CERT and add main/certs/mqtt_ca.pem", config->mqtt_broker_url);
+        return ESP_ERR_NOT_SUPPORTED;
+    }
+#endif
+    
+    esp_mqtt_client_config_t mqtt_cfg = {
+        .broker.address.uri = config->mqtt_broker_url,
+        .credentials.client_id = client_id,
+        .session.keepalive = 60,
+        .session.last_will = {
+            .topic = topic_status,
+            .msg = MQTT_STATUS_OFFLINE,
+            .msg_len = sizeof(MQTT_STATUS_OFFLINE) - 1,
+            .qos = 1,
+            .retain = 1,
+        },
+        // Reconnects are scheduled by this module with exponential backoff
+        .network.disable_auto_reconnect = true,
+    };
+    
+#if CONFIG_MQTT_USE_CA_CERT
+    if (use_tls) {
+        mqtt_cfg.broker.verification.certificate = mqtt_ca_pem_start;
+        ESP_LOGI(TAG, "MQTT using TLS with embedded CA certificate");
+    }
+#endif
+    
+    // Add authentication if username is configured
+    if (strlen(config->mqtt_username) > 0) {
+        mqtt_cfg.credentials.username = config->mqtt_username;
+        mqtt_cfg.credentials.authentication.password = config->mqtt_password;
+        ESP_LOGI(TAG, "MQTT using authentication with username: %s", config->mqtt_username);
+    }
+    
+    const esp_timer_create_args_t timer_args = {
+        .callback = reconnect_timer_cb,
+        .name = "mqtt_reconnect",
+    };
+    esp_err_t err = esp_timer_create(&timer_args, &reconnect_timer);
+    if (err != ESP_OK) {
+        return err;
+    }
+    
+    const esp_timer_create_args_t bootstrap_args = {
+        .callback = water_bootstrap_timer_cb,
+        .name = "mqtt_water_get",
+    };
+    err = esp_timer_create(&bootstrap_args, &water_bootstrap_timer);
+    if (err != ESP_OK) {
+        return err;
+    }
+    
+    mqtt_client = esp_mqtt_client_init(&mqtt_cfg);
+    if (mqtt_client == NULL) {
+        ESP_LOGE(TAG, "Failed to create MQTT client");
+        return ESP_FAIL;
+    }
+    esp_mqtt_client_register_event(mqtt_client, ESP_EVENT_ANY_ID, mqtt_event_handler, NULL);
+    backend_subscribe(device_state_listener, NULL);
+    return esp_mqtt_client_start(mqtt_client);
+}
+
+bool mqtt_is_connected(void)
+{
+    return mqtt_connected;
+}
+
+void mqtt_register_state_callback(mqtt_state_cb_t cb)
+{
+    state_cb = cb;
+}
+
+uint32_t mqtt_get_reconnect_count(void)
+{
+    return reconnect_count;
+}
+
+const char *mqtt_get_client_id(void)
+{
+    return client_id;
+}
+
+// Publish a payload allocated with cJSON_malloc/cJSON_Print*, then free it.
+// While disconnected the payload is queued instead (and ESP_OK returned).
+static esp_err_t publish_payload(const char *topic, char *payload, const mqtt_publish_opts_t *opts)
+{
+    if (opts->qos < 0 || opts->qos > 2) {
+        cJSON_free(payload);
+        return ESP_ERR_INVALID_ARG;
+    }
+    if (!mqtt_connected) {
+        pending_push(topic, payload, opts->qos, opts->retain);
+        return ESP_OK;
+    }
+    int msg_id = esp_mqtt_client_publish(mqtt_client, topic, payload, 0, opts->qos, opts->retain);
+    cJSON_free(payload);
+    if (msg_id < 0) {
+        ESP_LOGW(TAG, "Publish to %s failed", topic);
+        return ESP_FAIL;
+    }
+    return ESP_OK;
+}
+
+// Publish caller-owned data to any topic; the offline queue only holds our own topics
+esp_err_t mqtt_publish_retained(const char *topic, const char *payload, int len, bool retain)
+{
+    if (mqtt_client == NULL || !mqtt_connected) return ESP_ERR_INVALID_STATE;
+    if (topic == NULL || payload == NULL || len < 0) return ESP_ERR_INVALID_ARG;
+    
+    int msg_id = esp_mqtt_client_publish(mqtt_client, topic, payload, len, 1, retain ? 1 : 0);
+    if (msg_id < 0) {
+        ESP_LOGW(TAG, "Publish to %s failed", topic);
+        return ESP_FAIL;
+    }
+    return ESP_OK;
+}
+
+esp_err_t mqtt_publish(const char *topic, const char *payload, int len)
+{
+    return mqtt_publish_retained(topic, payload, len, false);
+}
+
+// Serialize and publish a JSON object, then free it
+static esp_err_t publish_json(const char *topic, cJSON *root, const mqtt_publish_opts_t *opts)
+{
+    if (root == NULL) {
+        return ESP_ERR_NO_MEM;
+    }
+    cJSON_AddNumberToObject(root, "seq", atomic_fetch_add(&publish_seq, 1) + 1);
+    char *payload = cJSON_PrintUnformatted(root);
+    cJSON_Delete(root);
+    if (payload == NULL) {
+        ESP_LOGE(TAG, "Failed to serialize payload for %s", topic);
+        return ESP_ERR_NO_MEM;
+    }
+    return publish_payload(topic, payload, opts);
+}
+
+// Publish light state to MQTT
+esp_err_t publish_light_state(const char* mode, int state, uint8_t level)
+{
+    const mqtt_publish_opts_t opts = MQTT_PUBLISH_OPTS_LIGHT_STATE();
+    return publish_light_state_opts(mode, state, level, &opts);
+}
+
+esp_err_t publish_light_state_opts(const char* mode, int state, uint8_t level,
+                                   const mqtt_publish_opts_t *opts)
+{
+    if (mqtt_client == NULL) return ESP_ERR_INVALID_STATE;
+    if (mode == NULL || opts == NULL || level > 100) return ESP_ERR_INVALID_ARG;
+    
+    cJSON *root = cJSON_CreateObject();
+    cJSON_AddStringToObject(root, "mode", mode);
+    cJSON_AddNumberToObject(root, "state", state);
+    cJSON_AddNumberToObject(root, "level", level);
+    return publish_json(topic_light_state, root, opts);
+}
+
+// Publish water level to MQTT
+esp_err_t publish_water_level(uint8_t level)
+{
+    const mqtt_publish_opts_t opts = MQTT_PUBLISH_OPTS_WATER_LEVEL();
+    return publish_water_level_opts(level, &opts);
+}
+
+esp_err_t publish_water_level_opts(uint8_t level, const mqtt_publish_opts_t *opts)
+{
+    if (mqtt_client == NULL) return ESP_ERR_INVALID_STATE;
+    if (opts == NULL || level > 100) return ESP_ERR_INVALID_ARG;
+    
+    cJSON *root = cJSON_CreateObject();
+    cJSON_AddNumberToObject(root, "level", level);
+    return publish_json(topic_water_level, root, opts);
+}
+
+// Publish a heartbeat with device health
+esp_err_t mqtt_publish_status(uint32_t uptime_s, uint32_t free_heap, uint32_t free_internal,
+                              int8_t rssi, const char *reset_reason)
+{
+    if (mqtt_client == NULL || !mqtt_connected) return ESP_ERR_INVALID_STATE;
+    
+    cJSON *root = cJSON_CreateObject();
+    cJSON_AddNumberToObject(root, "uptime_s", uptime_s);
+    cJSON_AddNumberToObject(root, "free_heap", free_heap);
+    cJSON_AddNumberToObject(root, "free_internal", free_internal);
+    cJSON_AddNumberToObject(root, "rssi", rssi);
+    cJSON_AddNumberToObject(root, "reconnects", reconnect_count);
+    if (reset_reason != NULL) {
+        cJSON_AddStringToObject(root, "reset_reason", reset_reason);
+    }
+    const mqtt_publish_opts_t opts = { .qos = 0, .retain = false };
+    return publish_json(topic_telemetry, root, &opts);
+}
+
+// Publish the backend snapshot to MQTT
+esp_err_t publish_device_state(void)
+{
+    if (mqtt_client == NULL) return ESP_ERR_INVALID_STATE;
+    // The snapshot is retained: never replace the broker's copy with defaults
+    if (!backend_is_initialized()) return ESP_ERR_INVALID_STATE;
+    
+    // Room for the snapshot plus ,"seq":4294967295
+    const size_t size = BACKEND_JSON_MAX_LEN + 20;
+    char *payload = cJSON_malloc(size);
+    if (payload == NULL) return ESP_ERR_NO_MEM;
+    int len = backend_to_json(payload, BACKEND_JSON_MAX_LEN);
+    if (len < 2 || len >= BACKEND_JSON_MAX_LEN) {
+        cJSON_free(payload);
+        return ESP_FAIL;
+    }
+    // Replace the closing brace with the sequence number
+    snprintf(payload + len - 1, size - (len - 1), ",\"seq\":%u}",
+             atomic_fetch_add(&publish_seq, 1) + 1);
+    
+    const mqtt_publish_opts_t opts = { .qos = 1, .retain = true };
+    return publish_payload(topic_device_state, payload, &opts);
+}
@@ -0,0 +1,120 @@
+#ifndef MQTT_MANAGER_H
+#define MQTT_MANAGER_H
+
+#include <stdbool.h>
+#include <stdint.h>
+#include "esp_err.h"
+#include "sdkconfig.h"
+#include "app_config.h"
+
+// Client ID base; mqtt_init() appends the last three bytes of the eFuse MAC
+// ("sensecap_indicator_d1_a1b2c3") so every device gets its own. The full id
+// is also the base of Home Assistant unique_ids.
+#define MQTT_CLIENT_ID_BASE   "sensecap_indicator_d1"
+#define MQTT_CLIENT_ID_MAX_LEN 32
+
+// MQTT topics, relative to app_config_t.mqtt_topic_prefix (default
+// CONFIG_MQTT_TOPIC_PREFIX, "sensecap/indicator") so devices sharing a broker
+// don't collide
+#define MQTT_TOPIC_LIGHT_STATE   "light/state"
+#define MQTT_TOPIC_LIGHT_COMMAND "light/command"
+#define MQTT_TOPIC_WATER_LEVEL   "water/level"
+#define MQTT_TOPIC_STATUS        "status"
+#define MQTT_TOPIC_TELEMETRY     "telemetry"
+#define MQTT_TOPIC_DEVICE_STATE  "state"
+#define MQTT_TOPIC_WATER_GET     "water/get"
+#define MQTT_TOPIC_MAX_LEN       128
+
+// Prefix for Home Assistant discovery configs (CONFIG_MQTT_HA_DISCOVERY)
+#define MQTT_HA_DISCOVERY_PREFIX "homeassistant"
+
+// Availability payloads on MQTT_TOPIC_STATUS (retained; "offline" is the last will)
+#define MQTT_STATUS_ONLINE   "online"
+#define MQTT_STATUS_OFFLINE  "offline"
+
+// Reconnect backoff: 1s, 2s, 4s, ... capped at 60s
+#define MQTT_RECONNECT_MIN_DELAY_MS  1000
+#define MQTT_RECONNECT_MAX_DELAY_MS  60000
+
+// If no water level (retained or live) arrives this long after subscribing,
+// an empty request is published on MQTT_TOPIC_WATER_GET for the sensor node
+// to answer with its current reading on MQTT_TOPIC_WATER_LEVEL
+#define MQTT_WATER_BOOTSTRAP_TIMEOUT_MS  5000
+
+// Publishes made while disconnected are queued and flushed in order on reconnect
+#define MQTT_PENDING_QUEUE_LEN       8
+
+// Every JSON payload published by this module carries "seq": N, counting up
+// from 1 at boot across all topics (kept over reconnects), so a subscriber
+// can spot dropped messages. Discovery configs and mqtt_publish() payloads
+// are sent as-is.
+
+// Per-publish delivery options. qos is 0 (at most once), 1 (at least once)
+// or 2 (exactly once). Retained messages are stored by the broker and sent
+// to every new subscriber, so a dashboard that reconnects sees the current
+// value without waiting for the next change. Queued offline publishes keep
+// their options.
+typedef struct {
+    int qos;
+    bool retain;
+} mqtt_publish_opts_t;
+
+#define MQTT_PUBLISH_OPTS_LIGHT_STATE() { .qos = 1, .retain = true }
+#define MQTT_PUBLISH_OPTS_WATER_LEVEL() { .qos = CONFIG_MQTT_WATER_QOS, .retain = true }
+
+// Return values of mqtt_init() and the publish functions:
+//   ESP_OK                 published, or queued while disconnected
+//   ESP_ERR_INVALID_STATE  mqtt_init() has not run (or failed)
+//   ESP_ERR_INVALID_ARG    bad argument (NULL mode, level above 100, QoS outside 0-2, empty URL)
+//   ESP_ERR_NO_MEM         payload could not be built
+//   ESP_ERR_NOT_SUPPORTED  mqtts:// URL without CONFIG_MQTT_USE_CA_CERT
+//   ESP_FAIL               the MQTT client rejected the request
+
+// Initialize the MQTT client and start connecting to config->mqtt_broker_url
+// (the client keeps its own copies of the strings)
+esp_err_t mqtt_init(const app_config_t *config);
+
+// Get MQTT connection status
+bool mqtt_is_connected(void);
+
+// Called on the MQTT task whenever the connection comes up or drops, so the
+// UI can react instead of polling mqtt_is_connected(). Keep it short; NULL
+// unregisters. Can be set before mqtt_init().
+typedef void (*mqtt_state_cb_t)(bool connected);
+void mqtt_register_state_callback(mqtt_state_cb_t cb);
+
+// Number of reconnects since boot
+uint32_t mqtt_get_reconnect_count(void);
+
+// Per-device client ID (empty before mqtt_init)
+const char *mqtt_get_client_id(void);
+
+// Publish light state and brightness (0-100) to MQTT (retained, QoS1)
+esp_err_t publish_light_state(const char* mode, int state, uint8_t level);
+esp_err_t publish_light_state_opts(const char* mode, int state, uint8_t level,
+                                   const mqtt_publish_opts_t *opts);
+
+// Publish a locally measured water level (retained, CONFIG_MQTT_WATER_QOS)
+esp_err_t publish_water_level(uint8_t level);
+esp_err_t publish_water_level_opts(uint8_t level, const mqtt_publish_opts_t *opts);
+
+// Publish the full backend snapshot (backend_to_json) to <prefix>/state,
+// retained, QoS1. Sent on connect and after every backend state change;
+// ESP_ERR_INVALID_STATE before backend_init() so defaults never go out.
+esp_err_t publish_device_state(void);
+
+// Publish a heartbeat {"uptime_s","free_heap","free_internal","rssi","reconnects","reset_reason"}
+// (QoS0, not retained). Returns ESP_ERR_INVALID_STATE while disconnected:
+// heartbeats are dropped rather than queued, since a stale one is meaningless.
+esp_err_t mqtt_publish_status(uint32_t uptime_s, uint32_t free_heap, uint32_t free_internal,
+                              int8_t rssi, const char *reset_reason);
+
+// Publish an arbitrary payload to a full topic (not prefixed), QoS1. len is
+// the payload length in bytes, or 0 for a NUL-terminated string. The client
+// copies both strings. Unlike the publishers above these are not queued
+// offline (ESP_ERR_INVALID_STATE while disconnected). mqtt_publish() is the
+// non-retained shortcut.
+esp_err_t mqtt_publish(const char *topic, const char *payload, int len);
+esp_err_t mqtt_publish_retained(const char *topic, const char *payload, int len, bool retain);
+
+#endif // MQTT_MANAGER_H
@@ -0,0 +1,122 @@
+#include "selftest.h"
+#include "display_driver.h"
+#include "touch_driver.h"
+#include "lvgl.h"
+#include "driver/i2c.h"
+#include "esp_log.h"
+#include "freertos/FreeRTOS.h"
+#include "sdkconfig.h"
+
+static const char *TAG = "SELFTEST";
+
+// Shared I2C bus, installed by display_init()
+#define SELFTEST_I2C_NUM        I2C_NUM_0
+#define SELFTEST_TCA9535_ADDR   0x39
+
+#define SELFTEST_COLOR_STEP_MS  1000
+#define SELFTEST_TOUCH_POLL_MS  20
+
+// Full-screen fill sequence: red, green, blue, white, black
+static const uint32_t fill_colors[] = { 0xFF0000, 0x00FF00, 0x0000FF, 0xFFFFFF, 0x000000 };
+static const char *const fill_names[] = { "red", "green", "blue", "white", "black" };
+
+static lv_obj_t *screen = NULL;
+static lv_obj_t *cross_h = NULL;
+static lv_obj_t *cross_v = NULL;
+static lv_obj_t *status_label = NULL;
+static size_t color_index = 0;
+
+// An address-only write: the device ACKs if it is on the bus
+static bool i2c_probe(uint8_t addr)
+{
+    i2c_cmd_handle_t cmd = i2c_cmd_link_create();
+    i2c_master_start(cmd);
+    i2c_master_write_byte(cmd, (addr << 1) | I2C_MASTER_WRITE, true);
+    i2c_master_stop(cmd);
+    esp_err_t ret = i2c_master_cmd_begin(SELFTEST_I2C_NUM, cmd, pdMS_TO_TICKS(100));
+    i2c_cmd_link_delete(cmd);
+    return ret == ESP_OK;
+}
+
+bool selftest_requested(void)
+{
+#if CONFIG_SELFTEST_ON_BOOT
+    return true;
+#else
+    // A finger on the screen while booting enters the self-test
+    touch_point_t points[TOUCH_MAX_POINTS];
+    uint8_t count = 0;
+    return touch_read_points(points, TOUCH_MAX_POINTS, &count) == ESP_OK && count > 0;
+#endif
+}
+
+static void color_step_cb(lv_timer_t *timer)
+{
+    (void)timer;
+    color_index = (color_index + 1) % (sizeof(fill_colors) / sizeof(fill_colors[0]));
+    lv_obj_set_style_bg_color(screen, lv_color_hex(fill_colors[color_index]), LV_PART_MAIN);
+    
+    // Keep the crosshair and text visible on the white fill
+    lv_color_t fg = fill_colors[color_index] == 0xFFFFFF ? lv_color_black() : lv_color_white();
+    lv_obj_set_style_bg_color(cross_h, fg, LV_PART_MAIN);
+    lv_obj_set_style_bg_color(cross_v, fg, LV_PART_MAIN);
+    lv_obj_set_style_text_color(status_label, fg, LV_PART_MAIN);
+    ESP_LOGD(TAG, "Fill %s", fill_names[color_index]);
+}
+
+static void touch_follow_cb(lv_timer_t *timer)
+{
+    (void)timer;
+    int16_t x, y;
+    
+    // Crosshair tracks the first touch point and hides when nothing touches
+    if (touch_get_point_count() > 0 && touch_get_point(0, &x, &y) == ESP_OK) {
+        lv_obj_set_y(cross_h, y);
+        lv_obj_set_x(cross_v, x);
+        lv_obj_clear_flag(cross_h, LV_OBJ_FLAG_HIDDEN);
+        lv_obj_clear_flag(cross_v, LV_OBJ_FLAG_HIDDEN);
+    } else {
+        lv_obj_add_flag(cross_h, LV_OBJ_FLAG_HIDDEN);
+        lv_obj_add_flag(cross_v, LV_OBJ_FLAG_HIDDEN);
+    }
+}
+
+static lv_obj_t *create_line(lv_coord_t w, lv_coord_t h)
+{
+    lv_obj_t *line = lv_obj_create(screen);
+    lv_obj_remove_style_all(line);
+    lv_obj_set_size(line, w, h);
+    lv_obj_set_style_bg_opa(line, LV_OPA_COVER, LV_PART_MAIN);
+    lv_obj_set_style_bg_color(line, lv_color_white(), LV_PART_MAIN);
+    lv_obj_add_flag(line, LV_OBJ_FLAG_HIDDEN);
+    return line;
+}
+
+void selftest_start(void)
+{
+    ESP_LOGI(TAG, "Entering self-test (runs until reset)");
+    
+    bool tca9535_ok = i2c_probe(SELFTEST_TCA9535_ADDR);
+    bool gt911_ok = i2c_probe(touch_get_i2c_address());
+    ESP_LOGI(TAG, "TCA9535 (0x%02X): %s", SELFTEST_TCA9535_ADDR, tca9535_ok ? "PASS" : "FAIL");
+    ESP_LOGI(TAG, "GT911 (0x%02X): %s", touch_get_i2c_address(), gt911_ok ? "PASS" : "FAIL");
+    
+    screen = lv_obj_create(NULL);
+    lv_obj_clear_flag(screen, LV_OBJ_FLAG_SCROLLABLE);
+    lv_obj_set_style_bg_opa(screen, LV_OPA_COVER, LV_PART_MAIN);
+    lv_obj_set_style_bg_color(screen, lv_color_hex(fill_colors[0]), LV_PART_MAIN);
+    
+    status_label = lv_label_create(screen);
+    lv_label_set_text_fmt(status_label, "SELF-TEST\nTCA9535: %s\nGT911: %s",
+                          tca9535_ok ? "PASS" : "FAIL", gt911_ok ? "PASS" : "FAIL");
+    lv_obj_set_style_text_color(status_label, lv_color_white(), LV_PART_MAIN);
+    lv_obj_set_style_text_align(status_label, LV_TEXT_ALIGN_CENTER, LV_PART_MAIN);
+    lv_obj_center(status_label);
+    
+    cross_h = create_line(display_get_width(), 2);
+    cross_v = create_line(2, display_get_height());
+    
+    lv_disp_load_scr(screen);
+    lv_timer_create(color_step_cb, SELFTEST_COLOR_STEP_MS, NULL);
+    lv_timer_create(touch_follow_cb, SELFTEST_TOUCH_POLL_MS, NULL);
+}
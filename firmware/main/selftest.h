@@ -0,0 +1,16 @@
+#ifndef SELFTEST_H
+#define SELFTEST_H
+
+#include <stdbool.h>
+
+// True when the self-test should run instead of the normal UI: either
+// CONFIG_SELFTEST_ON_BOOT is set or the screen is being touched at boot.
+// Call after touch_init().
+bool selftest_requested(void);
+
+// Probe the I2C peripherals and show the color cycle / touch crosshair
+// screen. Call after display_driver_init() and touch_driver_init(); the
+// LVGL task drives it from then on and it runs until reset.
+void selftest_start(void);
+
+#endif // SELFTEST_H
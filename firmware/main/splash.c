@@ -0,0 +1,72 @@
+#include "splash.h"
+#include "esp_log.h"
+
+static const char *TAG = "SPLASH";
+
+#define SPLASH_FADE_MS  300
+
+static lv_obj_t *splash_screen = NULL;
+static lv_obj_t *progress_bar = NULL;
+static lv_obj_t *step_label = NULL;
+
+void splash_show(void)
+{
+    splash_screen = lv_obj_create(NULL);
+    lv_obj_clear_flag(splash_screen, LV_OBJ_FLAG_SCROLLABLE);
+    lv_obj_set_style_bg_color(splash_screen, lv_color_hex(0x000000), LV_PART_MAIN);
+    lv_obj_set_style_bg_opa(splash_screen, LV_OPA_COVER, LV_PART_MAIN);
+    
+    lv_obj_t *title = lv_label_create(splash_screen);
+    lv_label_set_text(title, "SenseCAP Indicator D1");
+    lv_obj_set_style_text_color(title, lv_color_hex(0x00C7EF), LV_PART_MAIN);
+    lv_obj_set_style_text_font(title, &lv_font_montserrat_24, LV_PART_MAIN);
+    lv_obj_align(title, LV_ALIGN_CENTER, 0, -30);
+    
+    progress_bar = lv_bar_create(splash_screen);
+    lv_obj_set_size(progress_bar, 240, 8);
+    lv_obj_align(progress_bar, LV_ALIGN_CENTER, 0, 20);
+    lv_obj_set_style_bg_color(progress_bar, lv_color_hex(0x202020), LV_PART_MAIN);
+    lv_obj_set_style_bg_color(progress_bar, lv_color_hex(0x00C7EF), LV_PART_INDICATOR);
+    lv_bar_set_range(progress_bar, 0, 100);
+    lv_bar_set_value(progress_bar, 0, LV_ANIM_OFF);
+    
+    step_label = lv_label_create(splash_screen);
+    lv_label_set_text(step_label, "Starting...");
+    lv_obj_set_style_text_color(step_label, lv_color_hex(0x808080), LV_PART_MAIN);
+    lv_obj_set_style_text_font(step_label, &lv_font_montserrat_14, LV_PART_MAIN);
+    lv_obj_align(step_label, LV_ALIGN_CENTER, 0, 45);
+    
+    lv_disp_load_scr(splash_screen);
+    lv_refr_now(NULL);
+}
+
+void splash_progress(uint8_t percent, const char *step)
+{
+    if (splash_screen == NULL) {
+        return;
+    }
+    if (percent > 100) percent = 100;
+    
+    ESP_LOGD(TAG, "%u%% %s", percent, step ? step : "");
+    if (lv_scr_act() != splash_screen) {
+        lv_disp_load_scr(splash_screen);
+    }
+    lv_bar_set_value(progress_bar, percent, LV_ANIM_OFF);
+    if (step != NULL) {
+        lv_label_set_text(step_label, step);
+    }
+    lv_refr_now(NULL);
+}
+
+void splash_finish(lv_obj_t *next)
+{
+    if (splash_screen == NULL || next == NULL) {
+        return;
+    }
+    
+    // auto_del frees the splash (and its children) once the fade is done
+    lv_scr_load_anim(next, LV_SCR_LOAD_ANIM_FADE_ON, SPLASH_FADE_MS, 0, true);
+    splash_screen = NULL;
+    progress_bar = NULL;
+    step_label = NULL;
+}
@@ -0,0 +1,22 @@
+#ifndef SPLASH_H
+#define SPLASH_H
+
+#include <stdint.h>
+#include "lvgl.h"
+
+// Boot splash shown while app_main brings up the UI, WiFi and MQTT. These
+// draw synchronously with lv_refr_now(), so they must run before the LVGL
+// task is started (nothing else touches LVGL at that point).
+
+// Load the splash screen and draw it; call right after display_driver_init()
+void splash_show(void);
+
+// Advance the progress bar (0-100) and show the step being started. Keeps
+// the splash on top even if another screen was loaded meanwhile (ui_init).
+void splash_progress(uint8_t percent, const char *step);
+
+// Fade over to next and free the splash. The fade runs once the LVGL task
+// is handling timers.
+void splash_finish(lv_obj_t *next);
+
+#endif // SPLASH_H
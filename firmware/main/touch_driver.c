@@ -1,42 +1,73 @@
 #include "touch_driver.h"
+#include "display_driver.h"
 #include "lvgl.h"
 #include "driver/i2c.h"
 #include "driver/gpio.h"
 #include "esp_log.h"
+#include "esp_timer.h"
+#include "sdkconfig.h"
+#include <stdlib.h>
+#include <string.h>
 
 static const char *TAG = "TOUCH";
 
 // Touch controller pins (GT911 for SenseCAP Indicator D1)
 // From official SDK: sensecap_indicator_board.c
-#define TOUCH_I2C_NUM       I2C_NUM_0
-#define TOUCH_PIN_NUM_SDA   39  // GPIO_I2C_SDA
-#define TOUCH_PIN_NUM_SCL   40  // GPIO_I2C_SCL
-#define TOUCH_PIN_NUM_INT   3
+#define TOUCH_I2C_NUM       I2C_NUM_0   // Shared bus (SDA 39, SCL 40), installed by display_init()
 #define TOUCH_PIN_NUM_RST   2
 
+// The GT911 INT output has no dedicated ESP32 GPIO on this board (GPIO3 is
+// R1 of the RGB bus), so touch is polled unless CONFIG_TOUCH_INT_GPIO names
+// a line that carries nothing else
+#define TOUCH_PIN_NUM_INT   CONFIG_TOUCH_INT_GPIO
+#if TOUCH_PIN_NUM_INT >= 0 && (TOUCH_PIN_NUM_INT <= 18 || TOUCH_PIN_NUM_INT == 21)
+#error "CONFIG_TOUCH_INT_GPIO is an RGB LCD pin; use a dedicated line or -1 to poll"
+#endif
+
+// GT911 I2C addresses: the INT level while RST is released selects one.
+// We don't drive INT, so both are probed
+#define GT911_ADDR_INT_LOW  0x5D
+#define GT911_ADDR_INT_HIGH 0x14
+
 // GT911 registers
-#define GT911_REG_X_LOW     0x8140
-#define GT911_REG_X_HIGH    0x8141
-#define GT911_REG_Y_LOW     0x8142
-#define GT911_REG_Y_HIGH    0x8143
+#define GT911_REG_PRODUCT_ID 0x8140 // 4 ASCII bytes (e.g. "911"), then firmware version (LE u16)
 #define GT911_REG_STATUS    0x814E
-#define GT911_REG_POINTS    0x814F
+#define GT911_REG_POINT1    0x814F  // Track ID, then X/Y (0x8150-0x8153) and size
+#define GT911_POINT_SIZE    8       // Point blocks are 8 bytes apart
+#define GT911_STATUS_READY  0x80
+#define GT911_STATUS_POINTS 0x0F
 
 static lv_indev_drv_t indev_drv;
+static uint8_t gt911_addr = GT911_ADDR_INT_LOW;  // Detected in touch_init()
 static int16_t last_x = 0;
 static int16_t last_y = 0;
 static bool last_pressed = false;
+static touch_calibration_t calibration = TOUCH_CALIBRATION_DEFAULT();
+static touch_gesture_detector_t gesture_detector = {0};
+static touch_gesture_cb_t gesture_cb = NULL;
+
+// Points from the most recent controller read, in screen coordinates
+static touch_point_t active_points[TOUCH_MAX_POINTS];
+static uint8_t active_count = 0;
+static bool indev_registered = false;
+
+// Set by a touch that woke the display; cleared on release so it never reaches LVGL
+static bool swallow_touch = false;
+
+// Set from the GT911 INT line ISR; only used with a dedicated CONFIG_TOUCH_INT_GPIO
+static volatile bool data_pending = false;
+static bool irq_enabled = false;
 
 // I2C read function
 static esp_err_t gt911_read(uint16_t reg, uint8_t *data, size_t len)
 {
     i2c_cmd_handle_t cmd = i2c_cmd_link_create();
     i2c_master_start(cmd);
-    i2c_master_write_byte(cmd, (0x5D << 1) | I2C_MASTER_WRITE, true);
+    i2c_master_write_byte(cmd, (gt911_addr << 1) | I2C_MASTER_WRITE, true);
     i2c_master_write_byte(cmd, reg >> 8, true);
     i2c_master_write_byte(cmd, reg & 0xFF, true);
     i2c_master_start(cmd);
-    i2c_master_write_byte(cmd, (0x5D << 1) | I2C_MASTER_READ, true);
+    i2c_master_write_byte(cmd, (gt911_addr << 1) | I2C_MASTER_READ, true);
     if (len > 1) {
         i2c_master_read(cmd, data, len - 1, I2C_MASTER_ACK);
     }
@@ -47,90 +78,445 @@ static esp_err_t gt911_read(uint16_t reg, uint8_t *data, size_t len)
     return ret;
 }
 
+// I2C write of a single register byte
+static esp_err_t gt911_write_byte(uint16_t reg, uint8_t value)
+{
+    i2c_cmd_handle_t cmd = i2c_cmd_link_create();
+    i2c_master_start(cmd);
+    i2c_master_write_byte(cmd, (gt911_addr << 1) | I2C_MASTER_WRITE, true);
+    i2c_master_write_byte(cmd, reg >> 8, true);
+    i2c_master_write_byte(cmd, reg & 0xFF, true);
+    i2c_master_write_byte(cmd, value, true);
+    i2c_master_stop(cmd);
+    esp_err_t ret = i2c_master_cmd_begin(TOUCH_I2C_NUM, cmd, pdMS_TO_TICKS(1000));
+    i2c_cmd_link_delete(cmd);
+    return ret;
+}
+
+// Find the controller: try the address selected during reset, then the other one
+static bool gt911_probe(uint8_t expected)
+{
+    const uint8_t candidates[2] = {
+        expected,
+        expected == GT911_ADDR_INT_LOW ? GT911_ADDR_INT_HIGH : GT911_ADDR_INT_LOW,
+    };
+    uint8_t status;
+    
+    for (int i = 0; i < 2; i++) {
+        gt911_addr = candidates[i];
+        if (gt911_read(GT911_REG_STATUS, &status, 1) == ESP_OK) {
+            if (i > 0) {
+                ESP_LOGW(TAG, "GT911 not at 0x%02X, found at 0x%02X", candidates[0], gt911_addr);
+            }
+            return true;
+        }
+    }
+    
+    gt911_addr = expected;
+    return false;
+}
+
+#if TOUCH_PIN_NUM_INT >= 0
+static void IRAM_ATTR touch_int_isr(void *arg)
+{
+    data_pending = true;
+}
+
+// Enable the INT pin interrupt; returns false if touch must fall back to polling
+static bool touch_irq_init(void)
+{
+    gpio_config_t int_gpio_config = {
+        .mode = GPIO_MODE_INPUT,
+        .pin_bit_mask = 1ULL << TOUCH_PIN_NUM_INT,
+        .pull_up_en = GPIO_PULLUP_DISABLE,
+        .intr_type = GPIO_INTR_NEGEDGE,  // GT911 pulses INT low when a report is ready
+    };
+    esp_err_t ret = gpio_config(&int_gpio_config);
+    if (ret != ESP_OK) {
+        return false;
+    }
+    
+    // The ISR service may already be installed by another driver
+    ret = gpio_install_isr_service(0);
+    if (ret != ESP_OK && ret != ESP_ERR_INVALID_STATE) {
+        return false;
+    }
+    
+    ret = gpio_isr_handler_add(TOUCH_PIN_NUM_INT, touch_int_isr, NULL);
+    return ret == ESP_OK;
+}
+#endif
+
 void touch_init(void)
 {
     ESP_LOGI(TAG, "Initializing touch hardware");
     
-    // Configure reset and interrupt GPIOs
     gpio_config_t rst_gpio_config = {
         .mode = GPIO_MODE_OUTPUT,
         .pin_bit_mask = 1ULL << TOUCH_PIN_NUM_RST
     };
     ESP_ERROR_CHECK(gpio_config(&rst_gpio_config));
     
-    gpio_config_t int_gpio_config = {
-        .mode = GPIO_MODE_INPUT,
-        .pin_bit_mask = 1ULL << TOUCH_PIN_NUM_INT,
-        .pull_up_en = GPIO_PULLUP_ENABLE
-    };
-    ESP_ERROR_CHECK(gpio_config(&int_gpio_config));
-    
-    // Reset touch controller
+    // GT911 power-on reset. INT is left alone (it is not an ESP32 line we
+    // own), so the controller may come up at either address
     gpio_set_level(TOUCH_PIN_NUM_RST, 0);
     vTaskDelay(pdMS_TO_TICKS(10));
     gpio_set_level(TOUCH_PIN_NUM_RST, 1);
-    vTaskDelay(pdMS_TO_TICKS(100));
-    
-    // Initialize I2C
-    i2c_config_t i2c_conf = {
-        .mode = I2C_MODE_MASTER,
-        .sda_io_num = TOUCH_PIN_NUM_SDA,
-        .scl_io_num = TOUCH_PIN_NUM_SCL,
-        .sda_pullup_en = GPIO_PULLUP_ENABLE,
-        .scl_pullup_en = GPIO_PULLUP_ENABLE,
-        .master.clk_speed = 400000,
-    };
+    vTaskDelay(pdMS_TO_TICKS(55));
+    
+    // The I2C bus is shared with the TCA9535 and already installed by display_init()
+    // Some modules ignore the INT strap, so confirm the address on the bus
+    if (gt911_probe(GT911_ADDR_INT_LOW)) {
+        ESP_LOGI(TAG, "GT911 found at I2C address 0x%02X", gt911_addr);
+        
+        char product_id[TOUCH_PRODUCT_ID_LEN];
+        uint16_t fw_version;
+        if (touch_read_product_info(product_id, &fw_version) == ESP_OK) {
+            ESP_LOGI(TAG, "GT911 product ID \"%s\", firmware 0x%04X", product_id, fw_version);
+        }
+    } else {
+        ESP_LOGE(TAG, "GT911 not responding at 0x%02X or 0x%02X",
+                 GT911_ADDR_INT_LOW, GT911_ADDR_INT_HIGH);
+    }
     
-    ESP_ERROR_CHECK(i2c_param_config(TOUCH_I2C_NUM, &i2c_conf));
-    ESP_ERROR_CHECK(i2c_driver_install(TOUCH_I2C_NUM, I2C_MODE_MASTER, 0, 0, 0));
+#if TOUCH_PIN_NUM_INT >= 0
+    irq_enabled = touch_irq_init();
+    if (irq_enabled) {
+        ESP_LOGI(TAG, "Touch interrupt enabled on GPIO%d", TOUCH_PIN_NUM_INT);
+    } else {
+        ESP_LOGW(TAG, "Touch interrupt setup failed, falling back to polling");
+    }
+#else
+    ESP_LOGI(TAG, "No dedicated touch INT line, polling the controller");
+#endif
     
     ESP_LOGI(TAG, "Touch hardware initialized");
 }
 
-void touch_read_cb(lv_indev_drv_t *drv, lv_indev_data_t *data)
+// Map a raw controller coordinate into screen space
+static void apply_calibration(touch_point_t *point)
+{
+    float x = point->x;
+    float y = point->y;
+    
+    if (calibration.swap_xy) {
+        float tmp = x;
+        x = y;
+        y = tmp;
+    }
+    
+    x = x * calibration.x_scale + calibration.x_offset;
+    y = y * calibration.y_scale + calibration.y_offset;
+    
+    if (calibration.invert_x) {
+        x = (display_get_width() - 1) - x;
+    }
+    if (calibration.invert_y) {
+        y = (display_get_height() - 1) - y;
+    }
+    
+    // Follow the panel mirror so touches land on what is drawn there
+    bool mirror_x, mirror_y;
+    display_get_mirror(&mirror_x, &mirror_y);
+    if (mirror_x) {
+        x = (display_get_width() - 1) - x;
+    }
+    if (mirror_y) {
+        y = (display_get_height() - 1) - y;
+    }
+    
+    // Edge noise can land slightly outside the panel; keep points on screen
+    float max_x = display_get_width() - 1;
+    float max_y = display_get_height() - 1;
+    if (x < 0) x = 0;
+    if (x > max_x) x = max_x;
+    if (y < 0) y = 0;
+    if (y > max_y) y = max_y;
+    
+    point->x = (int16_t)x;
+    point->y = (int16_t)y;
+}
+
+void touch_set_calibration(const touch_calibration_t *cal)
+{
+    if (cal == NULL) {
+        touch_calibration_t identity = TOUCH_CALIBRATION_DEFAULT();
+        calibration = identity;
+    } else {
+        calibration = *cal;
+    }
+    
+    ESP_LOGI(TAG, "Calibration: scale=(%.3f, %.3f) offset=(%.1f, %.1f) swap=%d invert=(%d, %d)",
+             calibration.x_scale, calibration.y_scale,
+             calibration.x_offset, calibration.y_offset,
+             calibration.swap_xy, calibration.invert_x, calibration.invert_y);
+}
+
+void touch_get_calibration(touch_calibration_t *cal)
+{
+    *cal = calibration;
+}
+
+esp_err_t touch_read_points(touch_point_t *points, uint8_t max_points, uint8_t *count)
 {
     uint8_t status = 0;
+    *count = 0;
     
     // Read touch status
-    if (gt911_read(GT911_REG_STATUS, &status, 1) != ESP_OK) {
+    esp_err_t ret = gt911_read(GT911_REG_STATUS, &status, 1);
+    if (ret != ESP_OK) {
+        return ret;
+    }
+    
+    // No new data from the controller
+    if (!(status & GT911_STATUS_READY)) {
+        return ESP_OK;
+    }
+    
+    uint8_t num_points = status & GT911_STATUS_POINTS;
+    if (num_points > TOUCH_MAX_POINTS) {
+        num_points = TOUCH_MAX_POINTS;
+    }
+    if (num_points > max_points) {
+        num_points = max_points;
+    }
+    
+    if (num_points > 0) {
+        uint8_t buf[TOUCH_MAX_POINTS * GT911_POINT_SIZE];
+        ret = gt911_read(GT911_REG_POINT1, buf, num_points * GT911_POINT_SIZE);
+        if (ret == ESP_OK) {
+            uint8_t valid = 0;
+            for (uint8_t i = 0; i < num_points; i++) {
+                const uint8_t *p = &buf[i * GT911_POINT_SIZE];
+                uint16_t raw_x = p[1] | (p[2] << 8);
+                uint16_t raw_y = p[3] | (p[4] << 8);
+                
+                // (0, 0) on a reported point is a known bogus reading; drop it
+                if (raw_x == 0 && raw_y == 0) {
+                    continue;
+                }
+                points[valid].id = p[0];
+                points[valid].x = raw_x;
+                points[valid].y = raw_y;
+                apply_calibration(&points[valid]);
+                valid++;
+            }
+            *count = valid;
+        }
+    }
+    
+    // Clear status only after all points have been read
+    gt911_write_byte(GT911_REG_STATUS, 0);
+    return ret;
+}
+
+touch_gesture_t touch_gesture_update(touch_gesture_detector_t *det, bool pressed,
+                                     int16_t x, int16_t y, uint32_t now_ms)
+{
+    if (pressed) {
+        if (!det->tracking) {
+            // Press-down: remember where and when the stroke started
+            det->tracking = true;
+            det->start_x = x;
+            det->start_y = y;
+            det->start_ms = now_ms;
+        }
+        det->last_x = x;
+        det->last_y = y;
+        return TOUCH_GESTURE_NONE;
+    }
+    
+    if (!det->tracking) {
+        return TOUCH_GESTURE_NONE;
+    }
+    det->tracking = false;
+    
+    // Release: classify by the dominant axis of movement
+    int dx = det->last_x - det->start_x;
+    int dy = det->last_y - det->start_y;
+    uint32_t duration = now_ms - det->start_ms;
+    
+    if (duration > TOUCH_SWIPE_MAX_TIME_MS) {
+        return TOUCH_GESTURE_NONE;
+    }
+    
+    if (abs(dx) >= abs(dy)) {
+        if (abs(dx) >= TOUCH_SWIPE_MIN_DISTANCE) {
+            return dx > 0 ? TOUCH_GESTURE_SWIPE_RIGHT : TOUCH_GESTURE_SWIPE_LEFT;
+        }
+    } else if (abs(dy) >= TOUCH_SWIPE_MIN_DISTANCE) {
+        return dy > 0 ? TOUCH_GESTURE_SWIPE_DOWN : TOUCH_GESTURE_SWIPE_UP;
+    }
+    
+    // Short movement: a tap, handled by the normal LVGL click path
+    return TOUCH_GESTURE_NONE;
+}
+
+void touch_set_gesture_callback(touch_gesture_cb_t cb)
+{
+    gesture_cb = cb;
+}
+
+esp_err_t touch_read_product_info(char *product_id, uint16_t *fw_version)
+{
+    if (product_id == NULL || fw_version == NULL) {
+        return ESP_ERR_INVALID_ARG;
+    }
+    
+    uint8_t buf[6];
+    esp_err_t ret = gt911_read(GT911_REG_PRODUCT_ID, buf, sizeof(buf));
+    if (ret != ESP_OK) {
+        ESP_LOGW(TAG, "Failed to read GT911 product info: %s", esp_err_to_name(ret));
+        return ret;
+    }
+    
+    // The ID is NUL-padded when shorter than 4 characters
+    memcpy(product_id, buf, 4);
+    product_id[4] = '\0';
+    *fw_version = buf[4] | (buf[5] << 8);
+    return ESP_OK;
+}
+
+uint8_t touch_get_i2c_address(void)
+{
+    return gt911_addr;
+}
+
+bool touch_data_ready(void)
+{
+    // Without a dedicated interrupt line every read goes to the controller
+    return !irq_enabled || data_pending;
+}
+
+esp_err_t touch_try_read_points(touch_point_t *points, uint8_t max_points, uint8_t *count)
+{
+    if (!touch_data_ready()) {
+        *count = 0;
+        return ESP_ERR_NOT_FINISHED;
+    }
+    data_pending = false;
+    
+    return touch_read_points(points, max_points, count);
+}
+
+int touch_poll(int16_t *x, int16_t *y, bool *pressed)
+{
+    static uint8_t poll_count = 0;
+    touch_point_t points[TOUCH_MAX_POINTS];
+    uint8_t count = 0;
+    
+    esp_err_t ret = touch_try_read_points(points, TOUCH_MAX_POINTS, &count);
+    if (ret == ESP_ERR_NOT_FINISHED) {
+        // Nothing new from the controller: same state as the last poll
+        count = poll_count;
+    } else if (ret != ESP_OK) {
+        return -1;
+    } else {
+        poll_count = count;
+        last_pressed = count > 0;
+        if (last_pressed) {
+            last_x = points[0].x;
+            last_y = points[0].y;
+        }
+    }
+    
+    // Panel coordinates on purpose: the caller's lv_indev gets LVGL's
+    // rotation handling, like touch_read_cb (see touch_driver.h)
+    if (x) *x = last_x;
+    if (y) *y = last_y;
+    if (pressed) *pressed = last_pressed;
+    return count;
+}
+
+void touch_read_cb(lv_indev_drv_t *drv, lv_indev_data_t *data)
+{
+    touch_point_t points[TOUCH_MAX_POINTS];
+    uint8_t count = 0;
+    
+    esp_err_t ret = touch_try_read_points(points, TOUCH_MAX_POINTS, &count);
+    
+    // No INT pulse since the last read: the controller has nothing new,
+    // so report the previous state without touching the I2C bus
+    if (ret == ESP_ERR_NOT_FINISHED) {
+        data->point.x = last_x;
+        data->point.y = last_y;
+        data->state = (last_pressed && !swallow_touch) ? LV_INDEV_STATE_PRESSED : LV_INDEV_STATE_RELEASED;
+        return;
+    }
+    
+    if (ret != ESP_OK) {
+        active_count = 0;
         data->state = LV_INDEV_STATE_RELEASED;
         return;
     }
     
-    // Check if touch is detected
-    if (status & 0x80) {
-        uint8_t buf[4];
-        if (gt911_read(GT911_REG_X_LOW, buf, 4) == ESP_OK) {
-            int16_t x = buf[0] | (buf[1] << 8);
-            int16_t y = buf[2] | (buf[3] << 8);
-            
-            // Transform coordinates (display is 480x480)
-            last_x = x;
-            last_y = y;
-            last_pressed = true;
-            
-            data->point.x = last_x;
-            data->point.y = last_y;
-            data->state = LV_INDEV_STATE_PRESSED;
-        } else {
-            data->state = LV_INDEV_STATE_RELEASED;
-        }
+    for (uint8_t i = 0; i < count; i++) {
+        active_points[i] = points[i];
+    }
+    active_count = count;
+    
+    // A touch on a sleeping display only wakes it, so it can't toggle a switch
+    if (count > 0 && display_is_asleep()) {
+        display_wake();
+        lv_disp_trig_activity(NULL);
+        swallow_touch = true;
+    }
+    
+    // LVGL pointer input only uses the first point
+    if (count > 0) {
+        last_x = points[0].x;
+        last_y = points[0].y;
+        last_pressed = true;
         
-        // Clear status
-        uint8_t clear = 0;
-        i2c_cmd_handle_t cmd = i2c_cmd_link_create();
-        i2c_master_start(cmd);
-        i2c_master_write_byte(cmd, (0x5D << 1) | I2C_MASTER_WRITE, true);
-        i2c_master_write_byte(cmd, GT911_REG_STATUS >> 8, true);
-        i2c_master_write_byte(cmd, GT911_REG_STATUS & 0xFF, true);
-        i2c_master_write_byte(cmd, clear, true);
-        i2c_master_stop(cmd);
-        i2c_master_cmd_begin(TOUCH_I2C_NUM, cmd, pdMS_TO_TICKS(1000));
-        i2c_cmd_link_delete(cmd);
+        data->point.x = last_x;
+        data->point.y = last_y;
+        data->state = swallow_touch ? LV_INDEV_STATE_RELEASED : LV_INDEV_STATE_PRESSED;
     } else {
         last_pressed = false;
+        swallow_touch = false;
+        data->point.x = last_x;
+        data->point.y = last_y;
         data->state = LV_INDEV_STATE_RELEASED;
     }
+    
+    if (swallow_touch) {
+        return;
+    }
+    
+    uint32_t now_ms = (uint32_t)(esp_timer_get_time() / 1000);
+    touch_gesture_t gesture = touch_gesture_update(&gesture_detector, count > 0,
+                                                   last_x, last_y, now_ms);
+    if (gesture != TOUCH_GESTURE_NONE) {
+        ESP_LOGD(TAG, "Gesture detected: %d", gesture);
+        if (gesture_cb != NULL) {
+            gesture_cb(gesture);
+        }
+    }
+}
+
+int touch_get_point_count(void)
+{
+    if (!indev_registered) {
+        return -1;
+    }
+    return active_count;
+}
+
+esp_err_t touch_get_point(uint8_t index, int16_t *x, int16_t *y)
+{
+    if (!indev_registered) {
+        return ESP_ERR_INVALID_STATE;
+    }
+    if (x == NULL || y == NULL || index >= active_count) {
+        return ESP_ERR_INVALID_ARG;
+    }
+    
+    // Points are stored in panel space; report them in the rotated frame the
+    // UI is laid out in, exactly as LVGL maps its own pointer input
+    display_rotation_unmap_point(display_get_rotation(),
+                                 display_get_width(), display_get_height(),
+                                 active_points[index].x, active_points[index].y, x, y);
+    return ESP_OK;
 }
 
 void touch_driver_init(void)
@@ -141,6 +527,7 @@ void touch_driver_init(void)
     indev_drv.type = LV_INDEV_TYPE_POINTER;
     indev_drv.read_cb = touch_read_cb;
     lv_indev_drv_register(&indev_drv);
+    indev_registered = true;
     
     ESP_LOGI(TAG, "LVGL touch driver initialized");
 }
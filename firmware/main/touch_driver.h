@@ -3,13 +3,122 @@
 
 #include <stdint.h>
 #include <stdbool.h>
+#include "esp_err.h"
 #include "lvgl.h"
 
-// Touch initialization
+// GT911 reports up to 5 simultaneous touch points
+#define TOUCH_MAX_POINTS 5
+
+typedef struct {
+    int16_t x;
+    int16_t y;
+    uint8_t id;     // Track ID assigned by the controller
+} touch_point_t;
+
+// Raw-to-screen transform, applied in order: swap, scale + offset, invert
+typedef struct {
+    float x_scale;
+    float x_offset;
+    float y_scale;
+    float y_offset;
+    bool swap_xy;
+    bool invert_x;   // Mirror X across the display width
+    bool invert_y;   // Mirror Y across the display height
+} touch_calibration_t;
+
+#define TOUCH_CALIBRATION_DEFAULT() {   \
+    .x_scale = 1.0f,                    \
+    .x_offset = 0.0f,                   \
+    .y_scale = 1.0f,                    \
+    .y_offset = 0.0f,                   \
+    .swap_xy = false,                   \
+    .invert_x = false,                  \
+    .invert_y = false,                  \
+}
+
+// Swipe detection thresholds
+#define TOUCH_SWIPE_MIN_DISTANCE  60    // Minimum travel in pixels
+#define TOUCH_SWIPE_MAX_TIME_MS   500   // Maximum press-to-release time
+
+typedef enum {
+    TOUCH_GESTURE_NONE = 0,
+    TOUCH_GESTURE_SWIPE_LEFT,
+    TOUCH_GESTURE_SWIPE_RIGHT,
+    TOUCH_GESTURE_SWIPE_UP,
+    TOUCH_GESTURE_SWIPE_DOWN
+} touch_gesture_t;
+
+// Tracks one press-to-release stroke
+typedef struct {
+    bool tracking;
+    int16_t start_x;
+    int16_t start_y;
+    int16_t last_x;
+    int16_t last_y;
+    uint32_t start_ms;
+} touch_gesture_detector_t;
+
+// Called from the LVGL task when a swipe is recognized
+typedef void (*touch_gesture_cb_t)(touch_gesture_t gesture);
+
+// Touch initialization (must run after display_init, which installs the shared I2C bus)
 void touch_init(void);
 void touch_driver_init(void);
 
-// Touch read callback for LVGL
+// GT911 product ID string, NUL-terminated (e.g. "911")
+#define TOUCH_PRODUCT_ID_LEN 5
+
+// Read the GT911 product ID (product_id must hold TOUCH_PRODUCT_ID_LEN bytes)
+// and firmware version; both are also logged by touch_init
+esp_err_t touch_read_product_info(char *product_id, uint16_t *fw_version);
+
+// I2C address the GT911 answered at during touch_init (0x5D or 0x14)
+uint8_t touch_get_i2c_address(void);
+
+// Read all active touch points; *count is 0 when nothing is touching.
+// Always queries the controller, even if it has nothing new
+esp_err_t touch_read_points(touch_point_t *points, uint8_t max_points, uint8_t *count);
+
+// Like touch_read_points, but returns ESP_ERR_NOT_FINISHED without any I2C
+// traffic when the INT line has not signalled new data since the last read
+// (with CONFIG_TOUCH_INT_GPIO; otherwise it always reads)
+esp_err_t touch_try_read_points(touch_point_t *points, uint8_t max_points, uint8_t *count);
+
+// True when the GT911 signalled new data on its INT line. Always true when
+// polling, which is the default: the board has no dedicated INT GPIO
+bool touch_data_ready(void);
+
+// Calibration applied to every reported point (NULL restores identity)
+void touch_set_calibration(const touch_calibration_t *cal);
+void touch_get_calibration(touch_calibration_t *cal);
+
+// Feed one touch sample; returns a swipe on release, TOUCH_GESTURE_NONE otherwise
+touch_gesture_t touch_gesture_update(touch_gesture_detector_t *det, bool pressed,
+                                     int16_t x, int16_t y, uint32_t now_ms);
+
+// Register a callback for swipes detected by the LVGL touch driver
+void touch_set_gesture_callback(touch_gesture_cb_t cb);
+
+// Number of points seen by the last LVGL touch read, or -1 before touch_driver_init
+int touch_get_point_count(void);
+
+// Screen-space coordinates of one point from the last LVGL touch read:
+// calibrated, then mapped through display_get_rotation() like LVGL's own
+// pointer input, so they match object coordinates at any rotation
+esp_err_t touch_get_point(uint8_t index, int16_t *x, int16_t *y);
+
+// For UIs that register their own lv_indev instead of calling
+// touch_driver_init (never both: each read clears the controller status).
+// Fills the calibrated first point and whether a finger is down; while
+// released x/y keep the last position, as LVGL expects. Returns the number
+// of points, or -1 on an I2C error.
+// x/y are unrotated panel coordinates. Feed them to a pointer lv_indev on
+// the display: LVGL applies display_set_rotation() to pointer input itself,
+// so rotating them here would rotate twice. Other callers that need screen
+// coordinates map them with display_rotation_unmap_point().
+int touch_poll(int16_t *x, int16_t *y, bool *pressed);
+
+// Touch read callback for LVGL (first point only)
 void touch_read_cb(lv_indev_drv_t *drv, lv_indev_data_t *data);
 
 #endif // TOUCH_DRIVER_H
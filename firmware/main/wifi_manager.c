@@ -1,5 +1,6 @@
 #include "wifi_manager.h"
 #include <string.h>
+#include <stdlib.h>
 #include "freertos/FreeRTOS.h"
 #include "freertos/event_groups.h"
 #include "esp_wifi.h"
@@ -7,6 +8,7 @@
 #include "esp_event.h"
 #include "esp_log.h"
 #include "esp_system.h"
+#include "esp_timer.h"
 
 static const char *TAG = "WIFI";
 
@@ -18,30 +20,66 @@ static esp_netif_t *sta_netif = NULL;
 static bool wifi_connected = false;
 static char ip_addr[16] = {0};
 static int s_retry_num = 0;
+static esp_timer_handle_t s_reconnect_timer = NULL;
+static bool s_wifi_started = false;      // STA interface running (connect or scan)
+static bool s_connect_requested = false; // wifi_connect() called; reconnect on drop
+
+// Reconnect backoff: 1s, 2s, 4s, ... capped at 60s
+#define WIFI_RECONNECT_MIN_DELAY_MS  1000
+#define WIFI_RECONNECT_MAX_DELAY_MS  60000
+
+static void reconnect_timer_cb(void *arg)
+{
+    ESP_LOGI(TAG, "Reconnecting to WiFi (attempt %d)", s_retry_num);
+    esp_wifi_connect();
+}
+
+// Schedule the next connection attempt with exponential backoff
+static void schedule_reconnect(void)
+{
+    uint32_t delay_ms = WIFI_RECONNECT_MIN_DELAY_MS;
+    for (int i = 0; i < s_retry_num && delay_ms < WIFI_RECONNECT_MAX_DELAY_MS; i++) {
+        delay_ms *= 2;
+    }
+    if (delay_ms > WIFI_RECONNECT_MAX_DELAY_MS) {
+        delay_ms = WIFI_RECONNECT_MAX_DELAY_MS;
+    }
+    s_retry_num++;
+    
+    ESP_LOGI(TAG, "Retrying WiFi connection in %u ms", (unsigned)delay_ms);
+    esp_timer_stop(s_reconnect_timer);
+    esp_timer_start_once(s_reconnect_timer, (uint64_t)delay_ms * 1000);
+}
 
 static void wifi_event_handler(void *arg, esp_event_base_t event_base,
                                int32_t event_id, void *event_data)
 {
     if (event_base == WIFI_EVENT && event_id == WIFI_EVENT_STA_START) {
-        esp_wifi_connect();
+        s_wifi_started = true;
+        // The interface may have been started just to scan
+        if (s_connect_requested) {
+            esp_wifi_connect();
+        }
     } else if (event_base == WIFI_EVENT && event_id == WIFI_EVENT_STA_DISCONNECTED) {
         wifi_event_sta_disconnected_t *event = (wifi_event_sta_disconnected_t *)event_data;
         ESP_LOGI(TAG, "WiFi disconnected, reason: %d", event->reason);
         wifi_connected = false;
+        ip_addr[0] = '\0';
+        xEventGroupClearBits(s_wifi_event_group, WIFI_CONNECTED_BIT);
         
-        if (s_retry_num < CONFIG_WIFI_MAXIMUM_RETRY) {
-            esp_wifi_connect();
-            s_retry_num++;
-            ESP_LOGI(TAG, "Retry connecting to WiFi...");
-        } else {
+        // Keep retrying forever; after CONFIG_WIFI_MAXIMUM_RETRY attempts
+        // wifi_connect() stops waiting so boot can continue offline
+        if (s_retry_num >= CONFIG_WIFI_MAXIMUM_RETRY) {
             xEventGroupSetBits(s_wifi_event_group, WIFI_FAIL_BIT);
         }
+        schedule_reconnect();
     } else if (event_base == IP_EVENT && event_id == IP_EVENT_STA_GOT_IP) {
         ip_event_got_ip_t *event = (ip_event_got_ip_t *)event_data;
         snprintf(ip_addr, sizeof(ip_addr), IPSTR, IP2STR(&event->ip_info.ip));
         ESP_LOGI(TAG, "Got IP: %s", ip_addr);
         s_retry_num = 0;
         wifi_connected = true;
+        xEventGroupClearBits(s_wifi_event_group, WIFI_FAIL_BIT);
         xEventGroupSetBits(s_wifi_event_group, WIFI_CONNECTED_BIT);
     }
 }
@@ -52,6 +90,12 @@ void wifi_init(void)
     
     s_wifi_event_group = xEventGroupCreate();
     
+    const esp_timer_create_args_t timer_args = {
+        .callback = reconnect_timer_cb,
+        .name = "wifi_reconnect",
+    };
+    ESP_ERROR_CHECK(esp_timer_create(&timer_args, &s_reconnect_timer));
+    
     ESP_ERROR_CHECK(esp_netif_init());
     ESP_ERROR_CHECK(esp_event_loop_create_default());
     
@@ -95,9 +139,15 @@ void wifi_connect(const char *ssid, const char *password)
     strncpy((char *)wifi_config.sta.ssid, ssid, sizeof(wifi_config.sta.ssid));
     strncpy((char *)wifi_config.sta.password, password, sizeof(wifi_config.sta.password));
     
+    s_connect_requested = true;
     ESP_ERROR_CHECK(esp_wifi_set_mode(WIFI_MODE_STA));
     ESP_ERROR_CHECK(esp_wifi_set_config(WIFI_IF_STA, &wifi_config));
-    ESP_ERROR_CHECK(esp_wifi_start());
+    if (s_wifi_started) {
+        // Already started by wifi_scan(), so no STA_START event will connect for us
+        esp_wifi_connect();
+    } else {
+        ESP_ERROR_CHECK(esp_wifi_start());
+    }
     
     ESP_LOGI(TAG, "WiFi started, waiting for connection...");
     
@@ -110,7 +160,7 @@ void wifi_connect(const char *ssid, const char *password)
     if (bits & WIFI_CONNECTED_BIT) {
         ESP_LOGI(TAG, "Connected to AP SSID: %s", ssid);
     } else if (bits & WIFI_FAIL_BIT) {
-        ESP_LOGE(TAG, "Failed to connect to AP SSID: %s", ssid);
+        ESP_LOGE(TAG, "Failed to connect to AP SSID: %s, retrying in the background", ssid);
     } else {
         ESP_LOGE(TAG, "Unexpected event");
     }
@@ -121,6 +171,93 @@ bool wifi_is_connected(void)
     return wifi_connected;
 }
 
+// Strongest first
+static int compare_rssi_desc(const void *a, const void *b)
+{
+    return ((const wifi_ap_record_t *)b)->rssi - ((const wifi_ap_record_t *)a)->rssi;
+}
+
+esp_err_t wifi_scan(wifi_ap_info_t *aps, uint16_t max_aps, uint16_t *count)
+{
+    *count = 0;
+    
+    // Scanning needs the STA interface running, connected or not
+    if (!s_wifi_started) {
+        ESP_ERROR_CHECK(esp_wifi_set_mode(WIFI_MODE_STA));
+        esp_err_t err = esp_wifi_start();
+        if (err != ESP_OK) {
+            return err;
+        }
+    }
+    
+    esp_err_t err = esp_wifi_scan_start(NULL, true);
+    if (err != ESP_OK) {
+        ESP_LOGW(TAG, "WiFi scan failed: %s", esp_err_to_name(err));
+        return err;
+    }
+    
+    uint16_t num = 0;
+    esp_wifi_scan_get_ap_num(&num);
+    if (num == 0) {
+        return ESP_OK;
+    }
+    
+    wifi_ap_record_t *records = malloc(num * sizeof(wifi_ap_record_t));
+    if (records == NULL) {
+        esp_wifi_clear_ap_list();
+        return ESP_ERR_NO_MEM;
+    }
+    err = esp_wifi_scan_get_ap_records(&num, records);
+    if (err != ESP_OK) {
+        free(records);
+        return err;
+    }
+    
+    qsort(records, num, sizeof(wifi_ap_record_t), compare_rssi_desc);
+    
+    // Keep the strongest entry per SSID; skip hidden networks
+    for (uint16_t i = 0; i < num && *count < max_aps; i++) {
+        const char *ssid = (const char *)records[i].ssid;
+        if (ssid[0] == '\0') {
+            continue;
+        }
+        bool duplicate = false;
+        for (uint16_t j = 0; j < *count; j++) {
+            if (strcmp(aps[j].ssid, ssid) == 0) {
+                duplicate = true;
+                break;
+            }
+        }
+        if (duplicate) {
+            continue;
+        }
+        
+        wifi_ap_info_t *ap = &aps[(*count)++];
+        strncpy(ap->ssid, ssid, sizeof(ap->ssid) - 1);
+        ap->ssid[sizeof(ap->ssid) - 1] = '\0';
+        ap->rssi = records[i].rssi;
+        ap->authmode = records[i].authmode;
+    }
+    
+    free(records);
+    ESP_LOGI(TAG, "WiFi scan found %u networks", *count);
+    return ESP_OK;
+}
+
+bool wifi_get_rssi(int8_t *rssi)
+{
+    if (!wifi_connected) {
+        return false;
+    }
+    
+    wifi_ap_record_t ap_info;
+    if (esp_wifi_sta_get_ap_info(&ap_info) != ESP_OK) {
+        return false;
+    }
+    *rssi = ap_info.rssi;
+    return true;
+}
+
 const char* wifi_get_ip(void)
 {
     return wifi_connected ? ip_addr : NULL;
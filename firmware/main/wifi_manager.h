@@ -2,16 +2,34 @@
 #define WIFI_MANAGER_H
 
 #include <stdbool.h>
+#include <stdint.h>
+#include "esp_err.h"
+#include "esp_wifi_types.h"
+
+// Access point found by wifi_scan()
+typedef struct {
+    char ssid[33];
+    int8_t rssi;
+    wifi_auth_mode_t authmode;
+} wifi_ap_info_t;
 
 // WiFi initialization
 void wifi_init(void);
 
-// Connect to WiFi network
+// Connect to WiFi network. Blocks until connected or CONFIG_WIFI_MAXIMUM_RETRY
+// attempts failed; reconnects continue in the background with backoff either way.
 void wifi_connect(const char *ssid, const char *password);
 
 // Get WiFi connection status
 bool wifi_is_connected(void);
 
+// Scan for nearby networks (works before connecting). Fills up to max_aps
+// entries sorted by RSSI, strongest first, one per SSID; hidden networks are skipped.
+esp_err_t wifi_scan(wifi_ap_info_t *aps, uint16_t max_aps, uint16_t *count);
+
+// Get the current AP signal strength in dBm; returns false when not connected
+bool wifi_get_rssi(int8_t *rssi);
+
 // Get IP address as string
 const char* wifi_get_ip(void);
 
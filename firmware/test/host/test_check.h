@@ -0,0 +1,22 @@
+// Minimal assertion helpers for the host tests
+#ifndef TEST_CHECK_H
+#define TEST_CHECK_H
+
+#include <stdio.h>
+
+static int test_failures = 0;
+
+// Record a failure (with location) without aborting the test
+#define CHECK_EQ(actual, expected) do {                                     \
+    long a_ = (long)(actual), e_ = (long)(expected);                        \
+    if (a_ != e_) {                                                         \
+        printf("%s:%d: %s == %ld, expected %ld\n",                          \
+               __FILE__, __LINE__, #actual, a_, e_);                        \
+        test_failures++;                                                    \
+    }                                                                       \
+} while (0)
+
+// Exit status for main(): 0 when every check passed
+#define TEST_RESULT() (test_failures == 0 ? 0 : 1)
+
+#endif // TEST_CHECK_H
@@ -0,0 +1,160 @@
+// Check the logical -> panel pixel mapping used by display_flush_cb
+
+#include <stdbool.h>
+#include "display_rotation.h"
+#include "test_check.h"
+
+// Deliberately not square, so swapped width/height would show up
+#define PANEL_W 5
+#define PANEL_H 3
+
+// LVGL 8.3 lv_indev.c (indev_pointer_proc): panel touch point -> logical point
+static void lvgl_touch_to_logical(display_rotation_t rotation, int16_t px, int16_t py,
+                                  int16_t *x, int16_t *y)
+{
+    if (rotation == DISPLAY_ROTATION_180 || rotation == DISPLAY_ROTATION_270) {
+        px = PANEL_W - px - 1;
+        py = PANEL_H - py - 1;
+    }
+    if (rotation == DISPLAY_ROTATION_90 || rotation == DISPLAY_ROTATION_270) {
+        int16_t tmp = py;
+        py = px;
+        px = PANEL_H - tmp - 1;
+    }
+    *x = px;
+    *y = py;
+}
+
+static int16_t logical_w(display_rotation_t rotation)
+{
+    return (rotation == DISPLAY_ROTATION_90 || rotation == DISPLAY_ROTATION_270) ? PANEL_H : PANEL_W;
+}
+
+static int16_t logical_h(display_rotation_t rotation)
+{
+    return (rotation == DISPLAY_ROTATION_90 || rotation == DISPLAY_ROTATION_270) ? PANEL_W : PANEL_H;
+}
+
+static void test_corners(void)
+{
+    int16_t px, py;
+    
+    display_rotation_map_point(DISPLAY_ROTATION_0, PANEL_W, PANEL_H, 1, 2, &px, &py);
+    CHECK_EQ(px, 1);
+    CHECK_EQ(py, 2);
+    
+    // 90: logical is 3 wide, 5 tall; its top-left lands bottom-left on the panel
+    display_rotation_map_point(DISPLAY_ROTATION_90, PANEL_W, PANEL_H, 0, 0, &px, &py);
+    CHECK_EQ(px, 0);
+    CHECK_EQ(py, PANEL_H - 1);
+    display_rotation_map_point(DISPLAY_ROTATION_90, PANEL_W, PANEL_H, PANEL_H - 1, PANEL_W - 1, &px, &py);
+    CHECK_EQ(px, PANEL_W - 1);
+    CHECK_EQ(py, 0);
+    
+    display_rotation_map_point(DISPLAY_ROTATION_180, PANEL_W, PANEL_H, 0, 0, &px, &py);
+    CHECK_EQ(px, PANEL_W - 1);
+    CHECK_EQ(py, PANEL_H - 1);
+    
+    display_rotation_map_point(DISPLAY_ROTATION_270, PANEL_W, PANEL_H, 0, 0, &px, &py);
+    CHECK_EQ(px, PANEL_W - 1);
+    CHECK_EQ(py, 0);
+}
+
+// Every logical pixel lands on a distinct panel pixel, and touching that
+// panel pixel gives back the same logical pixel through LVGL's mapping
+static void test_matches_lvgl_touch_mapping(void)
+{
+    for (int r = DISPLAY_ROTATION_0; r <= DISPLAY_ROTATION_270; r++) {
+        display_rotation_t rotation = (display_rotation_t)r;
+        bool hit[PANEL_H][PANEL_W] = { { false } };
+        
+        for (int16_t y = 0; y < logical_h(rotation); y++) {
+            for (int16_t x = 0; x < logical_w(rotation); x++) {
+                int16_t px, py, tx, ty;
+                display_rotation_map_point(rotation, PANEL_W, PANEL_H, x, y, &px, &py);
+                CHECK_EQ(px >= 0 && px < PANEL_W && py >= 0 && py < PANEL_H, true);
+                if (px < 0 || px >= PANEL_W || py < 0 || py >= PANEL_H) {
+                    continue;
+                }
+                CHECK_EQ(hit[py][px], false);
+                hit[py][px] = true;
+                
+                lvgl_touch_to_logical(rotation, px, py, &tx, &ty);
+                CHECK_EQ(tx, x);
+                CHECK_EQ(ty, y);
+            }
+        }
+    }
+}
+
+static void test_copy_full_frame_90(void)
+{
+    // Logical frame for 90 degrees is PANEL_H x PANEL_W, numbered row-major
+    uint16_t src[PANEL_W * PANEL_H];
+    uint16_t dst[PANEL_W * PANEL_H];
+    for (int i = 0; i < PANEL_W * PANEL_H; i++) {
+        src[i] = (uint16_t)i;
+    }
+    
+    const display_area_t area = { 0, 0, PANEL_H - 1, PANEL_W - 1 };
+    display_area_t out;
+    display_rotation_map_area(DISPLAY_ROTATION_90, PANEL_W, PANEL_H, &area, &out);
+    CHECK_EQ(out.x1, 0);
+    CHECK_EQ(out.y1, 0);
+    CHECK_EQ(out.x2, PANEL_W - 1);
+    CHECK_EQ(out.y2, PANEL_H - 1);
+    
+    display_rotation_copy(DISPLAY_ROTATION_90, PANEL_W, PANEL_H, &area, src, dst);
+    // The logical right column becomes the top panel row, read top to bottom
+    const uint16_t top_row[PANEL_W] = { 2, 5, 8, 11, 14 };
+    const uint16_t bottom_row[PANEL_W] = { 0, 3, 6, 9, 12 };
+    for (int x = 0; x < PANEL_W; x++) {
+        CHECK_EQ(dst[x], top_row[x]);
+        CHECK_EQ(dst[(PANEL_H - 1) * PANEL_W + x], bottom_row[x]);
+    }
+}
+
+static void test_copy_partial_area(void)
+{
+    // A 2x2 block in the logical top-left corner, 180 degrees: it ends up in
+    // the panel's bottom-right corner, pixel order reversed
+    const display_area_t area = { 0, 0, 1, 1 };
+    const uint16_t src[4] = { 10, 11, 12, 13 };
+    uint16_t dst[4] = { 0 };
+    display_area_t out;
+    
+    display_rotation_map_area(DISPLAY_ROTATION_180, PANEL_W, PANEL_H, &area, &out);
+    CHECK_EQ(out.x1, PANEL_W - 2);
+    CHECK_EQ(out.y1, PANEL_H - 2);
+    CHECK_EQ(out.x2, PANEL_W - 1);
+    CHECK_EQ(out.y2, PANEL_H - 1);
+    
+    display_rotation_copy(DISPLAY_ROTATION_180, PANEL_W, PANEL_H, &area, src, dst);
+    CHECK_EQ(dst[0], 13);
+    CHECK_EQ(dst[1], 12);
+    CHECK_EQ(dst[2], 11);
+    CHECK_EQ(dst[3], 10);
+    
+    // A single logical row at 270 degrees becomes a panel column
+    const display_area_t row = { 0, 1, 2, 1 };
+    const uint16_t row_src[3] = { 1, 2, 3 };
+    uint16_t row_dst[3] = { 0 };
+    display_rotation_map_area(DISPLAY_ROTATION_270, PANEL_W, PANEL_H, &row, &out);
+    CHECK_EQ(out.x1, PANEL_W - 2);
+    CHECK_EQ(out.x2, PANEL_W - 2);
+    CHECK_EQ(out.y1, 0);
+    CHECK_EQ(out.y2, 2);
+    display_rotation_copy(DISPLAY_ROTATION_270, PANEL_W, PANEL_H, &row, row_src, row_dst);
+    CHECK_EQ(row_dst[0], 1);
+    CHECK_EQ(row_dst[1], 2);
+    CHECK_EQ(row_dst[2], 3);
+}
+
+int main(void)
+{
+    test_corners();
+    test_matches_lvgl_touch_mapping();
+    test_copy_full_frame_90();
+    test_copy_partial_area();
+    return TEST_RESULT();
+}
@@ -0,0 +1,73 @@
+// Check the panel -> logical mapping touch_get_point applies to touch points
+
+#include "display_rotation.h"
+#include "test_check.h"
+
+// Deliberately not square, so swapped width/height would show up
+#define PANEL_W 5
+#define PANEL_H 3
+
+// LVGL 8.3 lv_indev.c (indev_pointer_proc): what LVGL does to the same point
+static void lvgl_touch_to_logical(display_rotation_t rotation, int16_t px, int16_t py,
+                                  int16_t *x, int16_t *y)
+{
+    if (rotation == DISPLAY_ROTATION_180 || rotation == DISPLAY_ROTATION_270) {
+        px = PANEL_W - px - 1;
+        py = PANEL_H - py - 1;
+    }
+    if (rotation == DISPLAY_ROTATION_90 || rotation == DISPLAY_ROTATION_270) {
+        int16_t tmp = py;
+        py = px;
+        px = PANEL_H - tmp - 1;
+    }
+    *x = px;
+    *y = py;
+}
+
+static void test_examples(void)
+{
+    int16_t x, y;
+    
+    // Touching the panel's top-right corner
+    display_rotation_unmap_point(DISPLAY_ROTATION_0, PANEL_W, PANEL_H, PANEL_W - 1, 0, &x, &y);
+    CHECK_EQ(x, PANEL_W - 1);
+    CHECK_EQ(y, 0);
+    display_rotation_unmap_point(DISPLAY_ROTATION_90, PANEL_W, PANEL_H, PANEL_W - 1, 0, &x, &y);
+    CHECK_EQ(x, PANEL_H - 1);
+    CHECK_EQ(y, PANEL_W - 1);
+    display_rotation_unmap_point(DISPLAY_ROTATION_180, PANEL_W, PANEL_H, PANEL_W - 1, 0, &x, &y);
+    CHECK_EQ(x, 0);
+    CHECK_EQ(y, PANEL_H - 1);
+    display_rotation_unmap_point(DISPLAY_ROTATION_270, PANEL_W, PANEL_H, PANEL_W - 1, 0, &x, &y);
+    CHECK_EQ(x, 0);
+    CHECK_EQ(y, 0);
+}
+
+// Same result as LVGL for every panel pixel, and the inverse of the drawing
+// map, so a point reported to C callers lands where it was drawn
+static void test_matches_lvgl_and_drawing(void)
+{
+    for (int r = DISPLAY_ROTATION_0; r <= DISPLAY_ROTATION_270; r++) {
+        display_rotation_t rotation = (display_rotation_t)r;
+        for (int16_t py = 0; py < PANEL_H; py++) {
+            for (int16_t px = 0; px < PANEL_W; px++) {
+                int16_t x, y, lx, ly, dx, dy;
+                display_rotation_unmap_point(rotation, PANEL_W, PANEL_H, px, py, &x, &y);
+                lvgl_touch_to_logical(rotation, px, py, &lx, &ly);
+                CHECK_EQ(x, lx);
+                CHECK_EQ(y, ly);
+                
+                display_rotation_map_point(rotation, PANEL_W, PANEL_H, x, y, &dx, &dy);
+                CHECK_EQ(dx, px);
+                CHECK_EQ(dy, py);
+            }
+        }
+    }
+}
+
+int main(void)
+{
+    test_examples();
+    test_matches_lvgl_and_drawing();
+    return TEST_RESULT();
+}
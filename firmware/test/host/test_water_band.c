@@ -0,0 +1,90 @@
+// Walk the water level across the band boundaries and check the hysteresis
+
+#include "backend.h"
+#include "test_check.h"
+
+// Feed levels in order, starting from current, and return the final band
+static water_band_t walk(water_band_t current, const uint8_t *levels, size_t count)
+{
+    for (size_t i = 0; i < count; i++) {
+        current = backend_water_band_next(current, levels[i]);
+    }
+    return current;
+}
+
+static void test_falling_switches_at_threshold(void)
+{
+    CHECK_EQ(backend_water_band_next(WATER_BAND_NORMAL, 20), WATER_BAND_NORMAL);
+    CHECK_EQ(backend_water_band_next(WATER_BAND_NORMAL, 19), WATER_BAND_LOW);
+    CHECK_EQ(backend_water_band_next(WATER_BAND_LOW, 10), WATER_BAND_LOW);
+    CHECK_EQ(backend_water_band_next(WATER_BAND_LOW, 9), WATER_BAND_CRITICAL);
+    // A sudden drop skips the low band
+    CHECK_EQ(backend_water_band_next(WATER_BAND_NORMAL, 5), WATER_BAND_CRITICAL);
+}
+
+static void test_rising_needs_hysteresis(void)
+{
+    // Critical is only left at 10 + 3
+    CHECK_EQ(backend_water_band_next(WATER_BAND_CRITICAL, 10), WATER_BAND_CRITICAL);
+    CHECK_EQ(backend_water_band_next(WATER_BAND_CRITICAL, 12), WATER_BAND_CRITICAL);
+    CHECK_EQ(backend_water_band_next(WATER_BAND_CRITICAL, 13), WATER_BAND_LOW);
+    // Low is only left at 20 + 3
+    CHECK_EQ(backend_water_band_next(WATER_BAND_LOW, 20), WATER_BAND_LOW);
+    CHECK_EQ(backend_water_band_next(WATER_BAND_LOW, 22), WATER_BAND_LOW);
+    CHECK_EQ(backend_water_band_next(WATER_BAND_LOW, 23), WATER_BAND_NORMAL);
+    // A refill from critical goes straight to normal
+    CHECK_EQ(backend_water_band_next(WATER_BAND_CRITICAL, 100), WATER_BAND_NORMAL);
+}
+
+static void test_hover_on_boundary_keeps_band(void)
+{
+    // 9/10 noise on the critical threshold: one entry, no bouncing back
+    const uint8_t critical_hover[] = { 11, 9, 10, 9, 10, 11, 12, 9 };
+    water_band_t band = WATER_BAND_LOW;
+    int entries = 0;
+    for (size_t i = 0; i < sizeof(critical_hover); i++) {
+        water_band_t next = backend_water_band_next(band, critical_hover[i]);
+        if (next > band) {
+            entries++;
+        }
+        band = next;
+        CHECK_EQ(band, i == 0 ? WATER_BAND_LOW : WATER_BAND_CRITICAL);
+    }
+    CHECK_EQ(entries, 1);
+
+    // Same on the low threshold
+    const uint8_t low_hover[] = { 19, 20, 21, 19, 22, 20 };
+    CHECK_EQ(walk(WATER_BAND_NORMAL, low_hover, sizeof(low_hover)), WATER_BAND_LOW);
+}
+
+static void test_boundary_walk(void)
+{
+    // Down from full to empty, one point at a time
+    water_band_t band = WATER_BAND_NORMAL;
+    for (int level = 100; level >= 0; level--) {
+        band = backend_water_band_next(band, (uint8_t)level);
+        water_band_t expected = level < WATER_LEVEL_CRITICAL_THRESHOLD ? WATER_BAND_CRITICAL
+                              : level < WATER_LEVEL_LOW_THRESHOLD ? WATER_BAND_LOW
+                              : WATER_BAND_NORMAL;
+        CHECK_EQ(band, expected);
+    }
+
+    // And back up: each band is left WATER_LEVEL_HYSTERESIS points late
+    for (int level = 0; level <= 100; level++) {
+        band = backend_water_band_next(band, (uint8_t)level);
+        water_band_t expected =
+            level < WATER_LEVEL_CRITICAL_THRESHOLD + WATER_LEVEL_HYSTERESIS ? WATER_BAND_CRITICAL
+            : level < WATER_LEVEL_LOW_THRESHOLD + WATER_LEVEL_HYSTERESIS ? WATER_BAND_LOW
+            : WATER_BAND_NORMAL;
+        CHECK_EQ(band, expected);
+    }
+}
+
+int main(void)
+{
+    test_falling_switches_at_threshold();
+    test_rising_needs_hysteresis();
+    test_hover_on_boundary_keeps_band();
+    test_boundary_walk();
+    return TEST_RESULT();
+}
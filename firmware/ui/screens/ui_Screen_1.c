@@ -36,7 +36,147 @@ lv_obj_t * ui_Panel6 = NULL;
 lv_obj_t * ui_Panel7 = NULL;
 lv_obj_t * ui_RelaxSwitch = NULL;
 lv_obj_t * ui_BrightSwitch = NULL;
+lv_obj_t * ui_WifiIcon = NULL;
+lv_obj_t * ui_MqttDot = NULL;
+lv_obj_t * ui_WaterLiters = NULL;
+
+// Switch handle slide and indicator color crossfade
+#define UI_SWITCH_ANIM_TIME_MS 200
+
+static const lv_style_prop_t switch_trans_props[] = { LV_STYLE_BG_COLOR, 0 };
+static lv_style_transition_dsc_t switch_trans;
+
+static void ui_switch_add_animation(lv_obj_t * sw)
+{
+    static bool trans_inited = false;
+    if(!trans_inited) {
+        lv_style_transition_dsc_init(&switch_trans, switch_trans_props, lv_anim_path_ease_in_out,
+                                     UI_SWITCH_ANIM_TIME_MS, 0, NULL);
+        trans_inited = true;
+    }
+
+    // lv_switch slides the knob over the MAIN part's anim_time
+    lv_obj_set_style_anim_time(sw, UI_SWITCH_ANIM_TIME_MS, LV_PART_MAIN | LV_STATE_DEFAULT);
+    // Off color for the indicator so there is something to fade from
+    lv_obj_set_style_bg_color(sw, lv_color_hex(0xAAAAAA), LV_PART_INDICATOR | LV_STATE_DEFAULT);
+    lv_obj_set_style_transition(sw, &switch_trans, LV_PART_INDICATOR | LV_STATE_DEFAULT);
+}
+
+// Long-press detection on the water panel
+#define UI_LONG_PRESS_TIME_MS 700
+#define UI_LONG_PRESS_SLOP_PX 10
+
+static lv_point_t press_start_point;
+static uint32_t press_start_tick = 0;
+static bool long_press_fired = false;
+
+// Horizontal drag across the water panel sets the level (0-100) from x
+static bool water_dragging = false;
+static int water_drag_level = 0;
+
+static int ui_water_level_from_x(lv_coord_t x)
+{
+    lv_area_t area;
+    lv_obj_get_coords(ui_ArcContainer, &area);
+    lv_coord_t width = lv_area_get_width(&area);
+    if(width <= 1) return 0;
+
+    int level = ((x - area.x1) * 100) / (width - 1);
+    if(level < 0) level = 0;
+    if(level > 100) level = 100;
+    return level;
+}
+
+bool ui_Screen_1_water_dragging(void)
+{
+    return water_dragging;
+}
+
+// Double-tap anywhere on the lights panel turns both lights off
+#define UI_DOUBLE_TAP_TIME_MS 300
+#define UI_DOUBLE_TAP_SLOP_PX 40
+
+static lv_point_t last_tap_point;
+static uint32_t last_tap_tick = 0;
+static bool last_tap_valid = false;
+
 // event funtions
+void ui_event_LightsTap(lv_event_t * e)
+{
+    if(lv_event_get_code(e) != LV_EVENT_SHORT_CLICKED) return;
+
+    lv_indev_t * indev = lv_indev_get_act();
+    if(indev == NULL) return;
+
+    lv_point_t point;
+    lv_indev_get_point(indev, &point);
+
+    // Taps are never delayed: a single tap on a switch has already toggled it
+    if(last_tap_valid && lv_tick_elaps(last_tap_tick) <= UI_DOUBLE_TAP_TIME_MS &&
+       LV_ABS(point.x - last_tap_point.x) <= UI_DOUBLE_TAP_SLOP_PX &&
+       LV_ABS(point.y - last_tap_point.y) <= UI_DOUBLE_TAP_SLOP_PX) {
+        last_tap_valid = false;
+        UI_LOG(BACKEND_LOG_INFO, "Lights panel double-tap: all lights off");
+        _ui_state_modify(ui_BrightSwitch, LV_STATE_CHECKED, _UI_MODIFY_STATE_REMOVE);
+        _ui_state_modify(ui_RelaxSwitch, LV_STATE_CHECKED, _UI_MODIFY_STATE_REMOVE);
+        backend_set_light_mode(LIGHT_MODE_OFF);
+        return;
+    }
+
+    last_tap_point = point;
+    last_tap_tick = lv_tick_get();
+    last_tap_valid = true;
+}
+
+void ui_event_ArcContainer(lv_event_t * e)
+{
+    lv_event_code_t event_code = lv_event_get_code(e);
+    lv_indev_t * indev = lv_indev_get_act();
+    if(indev == NULL) return;
+
+    lv_point_t point;
+    lv_indev_get_point(indev, &point);
+
+    if(event_code == LV_EVENT_PRESSED) {
+        press_start_point = point;
+        press_start_tick = lv_tick_get();
+        long_press_fired = false;
+        water_dragging = false;
+    }
+    else if(event_code == LV_EVENT_PRESSING && !long_press_fired) {
+        // Past the slop horizontally, the press becomes a drag; update the arc live
+        if(!water_dragging && LV_ABS(point.x - press_start_point.x) > UI_LONG_PRESS_SLOP_PX) {
+            water_dragging = true;
+            water_drag_level = -1;  // Redraw on the first drag frame
+        }
+        if(water_dragging) {
+            int level = ui_water_level_from_x(point.x);
+            if(level != water_drag_level) {
+                water_drag_level = level;
+                ui_set_water_level(water_drag_level);
+            }
+            return;
+        }
+
+        // Only a finger held roughly in place counts as a long-press
+        if(LV_ABS(point.x - press_start_point.x) > UI_LONG_PRESS_SLOP_PX ||
+           LV_ABS(point.y - press_start_point.y) > UI_LONG_PRESS_SLOP_PX) {
+            return;
+        }
+        if(lv_tick_elaps(press_start_tick) >= UI_LONG_PRESS_TIME_MS) {
+            long_press_fired = true;
+            UI_LOG(BACKEND_LOG_INFO, "Water panel long-press at (%d, %d)", point.x, point.y);
+            ui_water_long_press(point.x, point.y);
+        }
+    }
+    else if((event_code == LV_EVENT_RELEASED || event_code == LV_EVENT_PRESS_LOST) && water_dragging) {
+        // Apply once at the end of the drag so MQTT isn't flooded
+        water_dragging = false;
+        UI_LOG(BACKEND_LOG_INFO, "Water level set by drag: %d%%", water_drag_level);
+        backend_set_water_level_local(water_drag_level);
+    }
+}
+
 void ui_event_RelaxSwitch(lv_event_t * e)
 {
     lv_event_code_t event_code = lv_event_get_code(e);
@@ -44,15 +184,15 @@ void ui_event_RelaxSwitch(lv_event_t * e)
 
     if(event_code == LV_EVENT_VALUE_CHANGED) {
         uint8_t state = lv_obj_has_state(target, LV_STATE_CHECKED) ? 1 : 0;
-        printf("[UI] Relax switch changed: %d\n", state);
+        UI_LOG(BACKEND_LOG_DEBUG, "Relax switch changed: %d", state);
         
         // Update UI mutual exclusion
         if(state) {
             _ui_state_modify(ui_BrightSwitch, LV_STATE_CHECKED, _UI_MODIFY_STATE_REMOVE);
         }
         
-        // Call backend to publish to MQTT
-        backend_set_relax(state);
+        // Set the mode in one step so both switches never read off mid-change
+        backend_set_light_mode(state ? LIGHT_MODE_RELAX : LIGHT_MODE_OFF);
     }
 }
 
@@ -63,15 +203,15 @@ void ui_event_BrightSwitch(lv_event_t * e)
 
     if(event_code == LV_EVENT_VALUE_CHANGED) {
         uint8_t state = lv_obj_has_state(target, LV_STATE_CHECKED) ? 1 : 0;
-        printf("[UI] Bright switch changed: %d\n", state);
+        UI_LOG(BACKEND_LOG_DEBUG, "Bright switch changed: %d", state);
         
         // Update UI mutual exclusion
         if(state) {
             _ui_state_modify(ui_RelaxSwitch, LV_STATE_CHECKED, _UI_MODIFY_STATE_REMOVE);
         }
         
-        // Call backend to publish to MQTT
-        backend_set_bright(state);
+        // Set the mode in one step so both switches never read off mid-change
+        backend_set_light_mode(state ? LIGHT_MODE_BRIGHT : LIGHT_MODE_OFF);
     }
 }
 
@@ -91,7 +231,7 @@ void ui_Screen_1_screen_init(void)
     lv_obj_set_x(ui_ArcContainer, -2);
     lv_obj_set_y(ui_ArcContainer, 119);
     lv_obj_set_align(ui_ArcContainer, LV_ALIGN_CENTER);
-    lv_obj_clear_flag(ui_ArcContainer, LV_OBJ_FLAG_CLICKABLE | LV_OBJ_FLAG_SCROLLABLE);      /// Flags
+    lv_obj_clear_flag(ui_ArcContainer, LV_OBJ_FLAG_SCROLLABLE);      /// Flags
     lv_obj_set_style_radius(ui_ArcContainer, 20, LV_PART_MAIN | LV_STATE_DEFAULT);
     lv_obj_set_style_bg_color(ui_ArcContainer, lv_color_hex(0x282828), LV_PART_MAIN | LV_STATE_DEFAULT);
     lv_obj_set_style_bg_opa(ui_ArcContainer, 255, LV_PART_MAIN | LV_STATE_DEFAULT);
@@ -106,7 +246,7 @@ void ui_Screen_1_screen_init(void)
     lv_obj_set_x(ui_LightContainer, 0);
     lv_obj_set_y(ui_LightContainer, -127);
     lv_obj_set_align(ui_LightContainer, LV_ALIGN_CENTER);
-    lv_obj_clear_flag(ui_LightContainer, LV_OBJ_FLAG_CLICKABLE | LV_OBJ_FLAG_SCROLLABLE);      /// Flags
+    lv_obj_clear_flag(ui_LightContainer, LV_OBJ_FLAG_SCROLLABLE);      /// Flags
     lv_obj_set_style_radius(ui_LightContainer, 20, LV_PART_MAIN | LV_STATE_DEFAULT);
     lv_obj_set_style_bg_color(ui_LightContainer, lv_color_hex(0x282828), LV_PART_MAIN | LV_STATE_DEFAULT);
     lv_obj_set_style_bg_opa(ui_LightContainer, 255, LV_PART_MAIN | LV_STATE_DEFAULT);
@@ -122,6 +262,7 @@ void ui_Screen_1_screen_init(void)
     lv_obj_set_align(ui_WaterTankArc, LV_ALIGN_CENTER);
     lv_arc_set_value(ui_WaterTankArc, 50);
     lv_arc_set_bg_angles(ui_WaterTankArc, 180, 360);
+    lv_obj_clear_flag(ui_WaterTankArc, LV_OBJ_FLAG_CLICKABLE);      /// Display only; presses reach ArcContainer
     lv_obj_set_style_radius(ui_WaterTankArc, 0, LV_PART_MAIN | LV_STATE_DEFAULT);
     lv_obj_set_style_arc_color(ui_WaterTankArc, lv_color_hex(0x000008), LV_PART_MAIN | LV_STATE_DEFAULT);
     lv_obj_set_style_arc_opa(ui_WaterTankArc, 255, LV_PART_MAIN | LV_STATE_DEFAULT);
@@ -339,6 +480,7 @@ void ui_Screen_1_screen_init(void)
 
     lv_obj_set_style_bg_color(ui_RelaxSwitch, lv_color_hex(0x000000), LV_PART_KNOB | LV_STATE_DEFAULT);
     lv_obj_set_style_bg_opa(ui_RelaxSwitch, 255, LV_PART_KNOB | LV_STATE_DEFAULT);
+    ui_switch_add_animation(ui_RelaxSwitch);
 
     ui_BrightSwitch = lv_switch_create(ui_Screen_1);
     lv_obj_set_width(ui_BrightSwitch, 93);
@@ -354,9 +496,50 @@ void ui_Screen_1_screen_init(void)
 
     lv_obj_set_style_bg_color(ui_BrightSwitch, lv_color_hex(0x000000), LV_PART_KNOB | LV_STATE_DEFAULT);
     lv_obj_set_style_bg_opa(ui_BrightSwitch, 255, LV_PART_KNOB | LV_STATE_DEFAULT);
-
+    ui_switch_add_animation(ui_BrightSwitch);
+
+    // Connection status, updated through ui_set_wifi_connected()
+    ui_WifiIcon = lv_label_create(ui_LightContainer);
+    lv_obj_set_align(ui_WifiIcon, LV_ALIGN_TOP_RIGHT);
+    lv_obj_set_x(ui_WifiIcon, -14);
+    lv_obj_set_y(ui_WifiIcon, 10);
+    lv_label_set_text(ui_WifiIcon, LV_SYMBOL_WIFI);
+    lv_obj_set_style_text_color(ui_WifiIcon, lv_color_hex(0x808080), LV_PART_MAIN | LV_STATE_DEFAULT);
+
+    // MQTT status dot, updated through ui_set_mqtt_connected()
+    ui_MqttDot = lv_obj_create(ui_ArcContainer);
+    lv_obj_remove_style_all(ui_MqttDot);
+    lv_obj_set_size(ui_MqttDot, 14, 14);
+    lv_obj_set_align(ui_MqttDot, LV_ALIGN_TOP_RIGHT);
+    lv_obj_set_x(ui_MqttDot, -16);
+    lv_obj_set_y(ui_MqttDot, 14);
+    lv_obj_clear_flag(ui_MqttDot, LV_OBJ_FLAG_CLICKABLE | LV_OBJ_FLAG_SCROLLABLE);
+    lv_obj_set_style_radius(ui_MqttDot, LV_RADIUS_CIRCLE, LV_PART_MAIN | LV_STATE_DEFAULT);
+    lv_obj_set_style_bg_color(ui_MqttDot, lv_color_hex(0x808080), LV_PART_MAIN | LV_STATE_DEFAULT);
+    lv_obj_set_style_bg_opa(ui_MqttDot, 255, LV_PART_MAIN | LV_STATE_DEFAULT);
+
+    // Approximate volume under the percentage, shown by ui_set_water_level() once a tank capacity is set
+    ui_WaterLiters = lv_label_create(ui_Screen_1);
+    lv_obj_set_align(ui_WaterLiters, LV_ALIGN_CENTER);
+    lv_obj_set_x(ui_WaterLiters, -2);
+    lv_obj_set_y(ui_WaterLiters, 224);
+    lv_label_set_text(ui_WaterLiters, "");
+    lv_obj_add_flag(ui_WaterLiters, LV_OBJ_FLAG_HIDDEN);
+    lv_obj_set_style_text_color(ui_WaterLiters, lv_color_hex(0x00C7EF), LV_PART_MAIN | LV_STATE_DEFAULT);
+    lv_obj_set_style_text_font(ui_WaterLiters, &lv_font_montserrat_14, LV_PART_MAIN | LV_STATE_DEFAULT);
+
+    lv_obj_add_event_cb(ui_ArcContainer, ui_event_ArcContainer, LV_EVENT_ALL, NULL);
     lv_obj_add_event_cb(ui_RelaxSwitch, ui_event_RelaxSwitch, LV_EVENT_ALL, NULL);
     lv_obj_add_event_cb(ui_BrightSwitch, ui_event_BrightSwitch, LV_EVENT_ALL, NULL);
+    // Everything clickable inside the lights panel feeds the double-tap detector
+    lv_obj_add_event_cb(ui_LightContainer, ui_event_LightsTap, LV_EVENT_SHORT_CLICKED, NULL);
+    lv_obj_add_event_cb(ui_BrightButtonPanel, ui_event_LightsTap, LV_EVENT_SHORT_CLICKED, NULL);
+    lv_obj_add_event_cb(ui_RelaxButtonPanel, ui_event_LightsTap, LV_EVENT_SHORT_CLICKED, NULL);
+    lv_obj_add_event_cb(ui_Panel3, ui_event_LightsTap, LV_EVENT_SHORT_CLICKED, NULL);
+    lv_obj_add_event_cb(ui_Panel4, ui_event_LightsTap, LV_EVENT_SHORT_CLICKED, NULL);
+    lv_obj_add_event_cb(ui_Panel5, ui_event_LightsTap, LV_EVENT_SHORT_CLICKED, NULL);
+    lv_obj_add_event_cb(ui_RelaxSwitch, ui_event_LightsTap, LV_EVENT_SHORT_CLICKED, NULL);
+    lv_obj_add_event_cb(ui_BrightSwitch, ui_event_LightsTap, LV_EVENT_SHORT_CLICKED, NULL);
     uic_Screen_1 = ui_Screen_1;
     uic_ArcContainer = ui_ArcContainer;
     uic_LightContainer = ui_LightContainer;
@@ -397,5 +580,8 @@ void ui_Screen_1_screen_destroy(void)
     ui_Panel7 = NULL;
     ui_RelaxSwitch = NULL;
     ui_BrightSwitch = NULL;
+    ui_WifiIcon = NULL;
+    ui_MqttDot = NULL;
+    ui_WaterLiters = NULL;
 
 }
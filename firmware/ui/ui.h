@@ -34,12 +34,46 @@ extern lv_obj_t * ui____initial_actions0;
 void ui_init(void);
 void ui_destroy(void);
 
+// Console logging for the UI, filtered by backend_set_log_level() like the
+// backend's own output (BACKEND_LOG_* levels; the including file must also
+// include the backend header)
+#define UI_LOG(level, fmt, ...) do {                           \
+    if ((level) <= backend_get_log_level()) {                  \
+        printf("[UI] " fmt "\n", ##__VA_ARGS__);               \
+    }                                                          \
+} while (0)
+
 // FFI Functions - called by Rust backend
+// Threading: ui_*_async() may be called from any thread (MQTT, backend);
+// they post the value and an LVGL timer applies it. Every other ui_*
+// function touches LVGL objects and must run on the LVGL thread.
 void ui_update_water_level_async(int level);
+void ui_set_bright_state_async(int state);
+void ui_set_relax_state_async(int state);
+void ui_show_water_critical_async(void);
+void ui_set_mqtt_connected_async(bool connected);
 void ui_set_water_level(int level);
 void ui_set_bright_state(int state);
 void ui_set_relax_state(int state);
 
+// Centered banner on the top layer, dismissed after duration_ms
+// (a new toast replaces one that is still showing)
+void ui_show_toast(const char * text, uint32_t duration_ms);
+
+// Connection status icons (call from the LVGL thread)
+void ui_set_wifi_connected(bool connected);
+void ui_set_wifi_rssi(int rssi);
+void ui_set_mqtt_connected(bool connected);
+
+// Vertical background gradient for the main screen, colors as 0xRRGGBB
+// (pass the same color twice for a flat background)
+void ui_set_background_gradient(uint32_t top_hex, uint32_t bottom_hex);
+
+// Create an image object from a raw RGB565 bitmap (w*h pixels, row-major).
+// With chroma_keyed set, pixels equal to LV_COLOR_CHROMA_KEY are not drawn.
+// The pixel data must stay valid for the lifetime of the object.
+lv_obj_t * ui_bitmap_create(lv_obj_t * parent, const uint16_t * data, uint16_t w, uint16_t h, bool chroma_keyed);
+
 #ifdef __cplusplus
 } /*extern "C"*/
 #endif
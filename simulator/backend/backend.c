@@ -5,47 +5,380 @@
 #include <stdlib.h>
 #include <string.h>
 #include <time.h>
+#include <ctype.h>
+#include <pthread.h>
 #include "backend.h"
 
 static int water_level = 75;  // Simulated water tank level (0-100)
-static bool light_bright = false;
-static bool light_relax = false;
+static water_band_t water_band = WATER_BAND_NORMAL;
+static light_mode_t light_mode = LIGHT_MODE_OFF;  // Bright and relax are mutually exclusive
+static int brightness = 100;  // Remembered across off/on
+static uint16_t tank_capacity_l = 0;  // 0 = unknown, percent only
+
+// Mock MQTT subscriber state
+static mock_config_t mock_config = MOCK_CONFIG_DEFAULT();
+static uint32_t rng_state = 1;
+static size_t sequence_index = 0;
+static uint32_t last_update_ms = 0;
+static int sensor_level = -1;  // MOCK_WATER_SENSOR tank level, -1 until first reading
+static int log_level = BACKEND_LOG_INFO;
+
+// printf with a level, filtered by backend_set_log_level()
+#define SIM_LOG(level, fmt, ...) do {                          \
+    if ((level) <= log_level) {                                \
+        printf("[SIMULATOR] " fmt "\n", ##__VA_ARGS__);        \
+    }                                                          \
+} while (0)
+
+// UI callbacks (same ones the firmware backend uses)
+void ui_update_water_level_async(int level);
+void ui_set_bright_state(int state);
+void ui_set_relax_state(int state);
+
+// Commands typed on stdin, queued by the reader thread and applied in backend_loop()
+typedef enum { CMD_BRIGHT, CMD_RELAX, CMD_OFF, CMD_WATER, CMD_REFILL } stdin_cmd_type_t;
+typedef struct {
+    stdin_cmd_type_t type;
+    int value;
+} stdin_cmd_t;
+
+#define STDIN_CMD_QUEUE_LEN 16
+static stdin_cmd_t cmd_queue[STDIN_CMD_QUEUE_LEN];
+static size_t cmd_head = 0;
+static size_t cmd_count = 0;
+static pthread_mutex_t cmd_lock = PTHREAD_MUTEX_INITIALIZER;
+
+static uint32_t now_ms(void) {
+    struct timespec ts;
+    clock_gettime(CLOCK_MONOTONIC, &ts);
+    return (uint32_t)(ts.tv_sec * 1000u + ts.tv_nsec / 1000000u);
+}
+
+// Mirrors backend_water_band_next() in the firmware: falling switches at the
+// threshold, rising needs WATER_LEVEL_HYSTERESIS extra points
+static water_band_t water_band_next(water_band_t current, int level) {
+    water_band_t band = WATER_BAND_NORMAL;
+    if (level < WATER_LEVEL_CRITICAL_THRESHOLD) {
+        band = WATER_BAND_CRITICAL;
+    } else if (level < WATER_LEVEL_LOW_THRESHOLD) {
+        band = WATER_BAND_LOW;
+    }
+    if (band >= current) {
+        return band;
+    }
+    if (current == WATER_BAND_CRITICAL &&
+        level < WATER_LEVEL_CRITICAL_THRESHOLD + WATER_LEVEL_HYSTERESIS) {
+        return WATER_BAND_CRITICAL;
+    }
+    if (level < WATER_LEVEL_LOW_THRESHOLD + WATER_LEVEL_HYSTERESIS) {
+        return WATER_BAND_LOW;
+    }
+    return WATER_BAND_NORMAL;
+}
+
+// Every water_level change ends here: update the band, then the UI
+static void water_level_changed(void) {
+    water_band = water_band_next(water_band, water_level);
+    ui_update_water_level_async(water_level);
+}
+
+// xorshift32 - small and identical on every platform, unlike rand()
+static uint32_t rng_next(void) {
+    rng_state ^= rng_state << 13;
+    rng_state ^= rng_state >> 17;
+    rng_state ^= rng_state << 5;
+    return rng_state;
+}
+
+// Simulated level sensor: the tank drains a little every reading and is
+// refilled when it gets close to empty, so the low and critical bands show up
+static int sensor_next_level(void) {
+    if (sensor_level < 0) {
+        sensor_level = water_level;
+    }
+    sensor_level -= mock_config.drain_per_update;
+    if (sensor_level < mock_config.refill_below) {
+        sensor_level = mock_config.refill_to;
+        SIM_LOG(BACKEND_LOG_INFO, "Sensor: tank refilled to %d%%", sensor_level);
+    }
+    if (sensor_level < 0) sensor_level = 0;
+    if (sensor_level > 100) sensor_level = 100;
+    return sensor_level;
+}
+
+static int mock_next_water_level(void) {
+    switch (mock_config.source) {
+        case MOCK_WATER_SENSOR:
+            return sensor_next_level();
+        case MOCK_WATER_FIXED:
+            return mock_config.fixed_level;
+        case MOCK_WATER_SEQUENCE:
+            if (mock_config.sequence == NULL || mock_config.sequence_len == 0) {
+                return water_level;
+            }
+            return mock_config.sequence[sequence_index++ % mock_config.sequence_len];
+        case MOCK_WATER_RANDOM:
+        default:
+            return (int)(rng_next() % 101);
+    }
+}
+
+static void print_command_usage(void) {
+    printf("[SIMULATOR] Commands: bright on|off, relax on|off, off, water <0-100>, refill\n");
+}
+
+// Parse "bright on", "relax off", "off", "water 35"; returns false on bad input
+static bool parse_command(const char *line, stdin_cmd_t *cmd) {
+    char name[16];
+    char arg[16];
+    int fields = sscanf(line, "%15s %15s", name, arg);
+    if (fields < 1) {
+        return false;
+    }
+    for (char *p = name; *p; p++) *p = (char)tolower((unsigned char)*p);
+    if (strcmp(name, "refill") == 0 || strcmp(name, "off") == 0) {
+        cmd->type = name[0] == 'r' ? CMD_REFILL : CMD_OFF;
+        cmd->value = 0;
+        return fields == 1;
+    }
+    if (fields != 2) {
+        return false;
+    }
+    for (char *p = arg; *p; p++) *p = (char)tolower((unsigned char)*p);
+
+    if (strcmp(name, "bright") == 0 || strcmp(name, "relax") == 0) {
+        cmd->type = name[0] == 'b' ? CMD_BRIGHT : CMD_RELAX;
+        if (strcmp(arg, "on") == 0) cmd->value = 1;
+        else if (strcmp(arg, "off") == 0) cmd->value = 0;
+        else return false;
+        return true;
+    }
+    if (strcmp(name, "water") == 0) {
+        char *end;
+        long level = strtol(arg, &end, 10);
+        if (*end != '\0' || level < 0 || level > 100) {
+            return false;
+        }
+        cmd->type = CMD_WATER;
+        cmd->value = (int)level;
+        return true;
+    }
+    return false;
+}
+
+static void *stdin_reader_thread(void *arg) {
+    (void)arg;
+    char line[64];
+    while (fgets(line, sizeof(line), stdin) != NULL) {
+        stdin_cmd_t cmd;
+        if (!parse_command(line, &cmd)) {
+            print_command_usage();
+            continue;
+        }
+        pthread_mutex_lock(&cmd_lock);
+        if (cmd_count < STDIN_CMD_QUEUE_LEN) {
+            cmd_queue[(cmd_head + cmd_count) % STDIN_CMD_QUEUE_LEN] = cmd;
+            cmd_count++;
+        } else {
+            SIM_LOG(BACKEND_LOG_WARN, "Command queue full, ignoring: %.*s", (int)strcspn(line, "\n"), line);
+        }
+        pthread_mutex_unlock(&cmd_lock);
+    }
+    return NULL;
+}
+
+// Apply queued stdin commands on the LVGL thread
+static void process_stdin_commands(void) {
+    for (;;) {
+        stdin_cmd_t cmd;
+        pthread_mutex_lock(&cmd_lock);
+        if (cmd_count == 0) {
+            pthread_mutex_unlock(&cmd_lock);
+            return;
+        }
+        cmd = cmd_queue[cmd_head];
+        cmd_head = (cmd_head + 1) % STDIN_CMD_QUEUE_LEN;
+        cmd_count--;
+        pthread_mutex_unlock(&cmd_lock);
+
+        switch (cmd.type) {
+            case CMD_BRIGHT:
+            case CMD_RELAX:
+                if (cmd.type == CMD_BRIGHT) backend_set_bright(cmd.value);
+                else backend_set_relax(cmd.value);
+                ui_set_bright_state(light_mode == LIGHT_MODE_BRIGHT);
+                ui_set_relax_state(light_mode == LIGHT_MODE_RELAX);
+                break;
+            case CMD_OFF:
+                backend_lights_all_off();
+                break;
+            case CMD_WATER:
+                water_level = cmd.value;
+                sensor_level = -1;  // The simulated sensor continues from here
+                SIM_LOG(BACKEND_LOG_INFO, "Water level (stdin): %d%%", water_level);
+                water_level_changed();
+                break;
+            case CMD_REFILL:
+                // A refill event for the simulated sensor; other sources just jump to full
+                sensor_level = mock_config.refill_to;
+                water_level = mock_config.refill_to;
+                SIM_LOG(BACKEND_LOG_INFO, "Tank refilled (stdin): %d%%", water_level);
+                water_level_changed();
+                break;
+        }
+    }
+}
+
+void backend_set_mock_config(const mock_config_t *config) {
+    mock_config = *config;
+    // xorshift must not start at zero
+    rng_state = config->seed != 0 ? config->seed : 1;
+    sequence_index = 0;
+    sensor_level = -1;
+    last_update_ms = now_ms();
+}
 
 void backend_init(void) {
-    printf("[SIMULATOR] Backend initialized\n");
-    printf("[SIMULATOR] Mock WiFi: Connected to 'Simulator-Network'\n");
-    printf("[SIMULATOR] Mock MQTT: Connected to localhost:1883\n");
+    SIM_LOG(BACKEND_LOG_INFO, "Backend initialized");
+    SIM_LOG(BACKEND_LOG_INFO, "Mock WiFi: Connected to 'Simulator-Network'");
+    SIM_LOG(BACKEND_LOG_INFO, "Mock MQTT: Connected to localhost:1883 (water level every %ums)",
+            (unsigned)mock_config.interval_ms);
+    last_update_ms = now_ms();
+
+    pthread_t reader;
+    if (pthread_create(&reader, NULL, stdin_reader_thread, NULL) == 0) {
+        pthread_detach(reader);
+        print_command_usage();
+    } else {
+        SIM_LOG(BACKEND_LOG_ERROR, "Failed to start stdin command reader");
+    }
 }
 
 void backend_set_light_mode(light_mode_t mode) {
+    light_mode = mode;
     switch(mode) {
         case LIGHT_MODE_BRIGHT:
-            light_bright = true;
-            light_relax = false;
-            printf("[SIMULATOR] Light mode: BRIGHT\n");
+            SIM_LOG(BACKEND_LOG_DEBUG, "Light mode: BRIGHT (%d%%)", brightness);
             break;
         case LIGHT_MODE_RELAX:
-            light_bright = false;
-            light_relax = true;
-            printf("[SIMULATOR] Light mode: RELAX\n");
+            SIM_LOG(BACKEND_LOG_DEBUG, "Light mode: RELAX (%d%%)", brightness);
             break;
         case LIGHT_MODE_OFF:
-            light_bright = false;
-            light_relax = false;
-            printf("[SIMULATOR] Light mode: OFF\n");
+            SIM_LOG(BACKEND_LOG_DEBUG, "Light mode: OFF");
             break;
     }
 }
 
+light_mode_t backend_get_light_mode(void) {
+    return light_mode;
+}
+
+int backend_get_light_mode_str(char *buf, size_t len) {
+    static const char *const names[] = {
+        [LIGHT_MODE_OFF] = "OFF",
+        [LIGHT_MODE_BRIGHT] = "BRIGHT",
+        [LIGHT_MODE_RELAX] = "RELAX",
+    };
+    if (buf == NULL) {
+        return -1;
+    }
+    const char *name = names[light_mode];
+    size_t name_len = strlen(name);
+    if (len <= name_len) {
+        return -1;
+    }
+    memcpy(buf, name, name_len + 1);
+    return (int)name_len;
+}
+
+// Same rules as the firmware backend: turning one light on turns the other
+// off, turning a light off only affects that light
+void backend_set_bright(bool on) {
+    if (on) {
+        backend_set_light_mode(LIGHT_MODE_BRIGHT);
+    } else if (light_mode == LIGHT_MODE_BRIGHT) {
+        backend_set_light_mode(LIGHT_MODE_OFF);
+    }
+}
+
+void backend_set_relax(bool on) {
+    if (on) {
+        backend_set_light_mode(LIGHT_MODE_RELAX);
+    } else if (light_mode == LIGHT_MODE_RELAX) {
+        backend_set_light_mode(LIGHT_MODE_OFF);
+    }
+}
+
+void backend_lights_all_off(void) {
+    backend_set_light_mode(LIGHT_MODE_OFF);
+    ui_set_bright_state(0);
+    ui_set_relax_state(0);
+}
+
+void backend_toggle_bright(void) {
+    backend_set_bright(light_mode != LIGHT_MODE_BRIGHT);
+}
+
+void backend_toggle_relax(void) {
+    backend_set_relax(light_mode != LIGHT_MODE_RELAX);
+}
+
+void backend_set_brightness(int level) {
+    if (level < 0) level = 0;
+    if (level > 100) level = 100;
+    brightness = level;
+    SIM_LOG(BACKEND_LOG_DEBUG, "Brightness: %d%%", brightness);
+}
+
+int backend_get_brightness(void) {
+    return brightness;
+}
+
+void backend_set_water_level_local(int level) {
+    if (level < 0) level = 0;
+    if (level > 100) level = 100;
+    water_level = level;
+    sensor_level = -1;
+    SIM_LOG(BACKEND_LOG_DEBUG, "Water level (local): %d%%", water_level);
+    SIM_LOG(BACKEND_LOG_DEBUG, "Mock MQTT publish: {\"level\":%d}", water_level);
+    water_level_changed();
+}
+
+void backend_water_mark_full(void) {
+    SIM_LOG(BACKEND_LOG_INFO, "Tank marked full");
+    backend_set_water_level_local(100);
+}
+
 int backend_get_water_level(void) {
-    // Simulate slowly changing water level
-    static int direction = -1;
-    water_level += direction;
-    if (water_level <= 10) direction = 1;
-    if (water_level >= 95) direction = -1;
     return water_level;
 }
 
+water_band_t backend_get_water_band(void) {
+    return water_band;
+}
+
+void backend_set_log_level(int level) {
+    if (level < BACKEND_LOG_NONE) level = BACKEND_LOG_NONE;
+    if (level > BACKEND_LOG_VERBOSE) level = BACKEND_LOG_VERBOSE;
+    log_level = level;
+}
+
+int backend_get_log_level(void) {
+    return log_level;
+}
+
+void backend_set_tank_capacity(uint16_t liters) {
+    tank_capacity_l = liters;
+}
+
+uint16_t backend_get_tank_capacity(void) {
+    return tank_capacity_l;
+}
+
+float backend_get_water_liters(void) {
+    return (float)water_level * tank_capacity_l / 100.0f;
+}
+
 bool backend_is_wifi_connected(void) {
     return true;  // Always connected in simulator
 }
@@ -55,10 +388,20 @@ bool backend_is_mqtt_connected(void) {
 }
 
 void backend_loop(void) {
-    // Simulator loop - can add periodic tasks here
-    static int counter = 0;
-    counter++;
-    if (counter % 60 == 0) {  // Every ~1 second at 60fps
-        printf("[SIMULATOR] Water level: %d%%\n", water_level);
+    process_stdin_commands();
+
+    // Mock MQTT subscriber: deliver the next water level every interval
+    uint32_t now = now_ms();
+    if (now - last_update_ms < mock_config.interval_ms) {
+        return;
     }
+    last_update_ms = now;
+
+    int level = mock_next_water_level();
+    if (level < 0) level = 0;
+    if (level > 100) level = 100;
+    water_level = level;
+    SIM_LOG(BACKEND_LOG_DEBUG, "%s water level: %d%%",
+            mock_config.source == MOCK_WATER_SENSOR ? "Sensor" : "Mock MQTT", water_level);
+    water_level_changed();
 }
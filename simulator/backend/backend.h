@@ -2,6 +2,8 @@
 #define BACKEND_H
 
 #include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
 
 // Light mode enum
 typedef enum {
@@ -10,20 +12,122 @@ typedef enum {
     LIGHT_MODE_RELAX = 2
 } light_mode_t;
 
-// Initialize backend (WiFi, MQTT, etc.)
+// Water level bands, same thresholds and hysteresis as the firmware backend:
+// a band is entered below its threshold and left once the level is
+// WATER_LEVEL_HYSTERESIS points above it
+#define WATER_LEVEL_LOW_THRESHOLD      20
+#define WATER_LEVEL_CRITICAL_THRESHOLD 10
+#define WATER_LEVEL_HYSTERESIS         3
+
+typedef enum {
+    WATER_BAND_NORMAL = 0,
+    WATER_BAND_LOW,
+    WATER_BAND_CRITICAL
+} water_band_t;
+
+// Where the mock MQTT subscriber gets its water levels from
+typedef enum {
+    MOCK_WATER_RANDOM = 0,   // Seeded pseudo-random 0-100 (reproducible)
+    MOCK_WATER_FIXED,        // Always fixed_level
+    MOCK_WATER_SEQUENCE,     // sequence[0..sequence_len), then loops
+    MOCK_WATER_SENSOR        // Simulated tank: drains steadily, refills when nearly empty
+} mock_water_source_t;
+
+// Mock MQTT configuration
+typedef struct {
+    uint32_t interval_ms;        // Time between mock water level messages
+    mock_water_source_t source;
+    uint32_t seed;               // MOCK_WATER_RANDOM
+    int fixed_level;             // MOCK_WATER_FIXED
+    const int *sequence;         // MOCK_WATER_SEQUENCE, must outlive the backend
+    size_t sequence_len;
+    int drain_per_update;        // MOCK_WATER_SENSOR: percent drained each interval
+    int refill_below;            // MOCK_WATER_SENSOR: refill once the level drops below this
+    int refill_to;               // MOCK_WATER_SENSOR: level after a refill
+} mock_config_t;
+
+#define MOCK_CONFIG_DEFAULT() {         \
+    .interval_ms = 5000,                \
+    .source = MOCK_WATER_RANDOM,        \
+    .seed = 1,                          \
+    .fixed_level = 50,                  \
+    .sequence = NULL,                   \
+    .sequence_len = 0,                  \
+    .drain_per_update = 3,              \
+    .refill_below = 5,                  \
+    .refill_to = 100,                   \
+}
+
+// Set the mock MQTT configuration (call before or after backend_init)
+void backend_set_mock_config(const mock_config_t *config);
+
+// Initialize backend (WiFi, MQTT, etc.) and start reading commands from
+// stdin: "bright on|off", "relax on|off", "off", "water <0-100>", "refill"
 void backend_init(void);
 
 // Set light mode
 void backend_set_light_mode(light_mode_t mode);
 
+// Get current light mode
+light_mode_t backend_get_light_mode(void);
+
+// Write "OFF", "BRIGHT" or "RELAX" (NUL-terminated) into buf; returns the
+// length without the NUL, or -1 if buf is NULL or too small (7 bytes fit every mode)
+int backend_get_light_mode_str(char *buf, size_t len);
+
+// Per-light control, matching the firmware backend's mutual exclusion
+void backend_set_bright(bool on);
+void backend_set_relax(bool on);
+
+// Both lights off and both switches cleared on screen, in one step
+void backend_lights_all_off(void);
+
+void backend_toggle_bright(void);
+void backend_toggle_relax(void);
+
+// Set/get light brightness (0-100); kept while the lights are off
+void backend_set_brightness(int level);
+int backend_get_brightness(void);
+
+// Set water level from the device itself (e.g. dragging on the water panel);
+// the real backend also publishes it over MQTT
+void backend_set_water_level_local(int level);
+
+// Mark the tank full (100%) after a refill; published like a local reading and
+// replaced by the next water level that arrives over (mock) MQTT
+void backend_water_mark_full(void);
+
 // Get current water tank level (0-100)
 int backend_get_water_level(void);
 
+// Band of the current water level; the UI colors the arc with it
+water_band_t backend_get_water_band(void);
+
+// Tank size in liters (0 = unknown, the UI shows percent only) and the
+// current volume, level * capacity / 100
+void backend_set_tank_capacity(uint16_t liters);
+uint16_t backend_get_tank_capacity(void);
+float backend_get_water_liters(void);
+
+// Log levels for backend_set_log_level(), numbered like the firmware's
+#define BACKEND_LOG_NONE    0
+#define BACKEND_LOG_ERROR   1
+#define BACKEND_LOG_WARN    2
+#define BACKEND_LOG_INFO    3
+#define BACKEND_LOG_DEBUG   4
+#define BACKEND_LOG_VERBOSE 5
+
+// Filter backend output (default BACKEND_LOG_INFO); light toggles and every
+// mock MQTT message are BACKEND_LOG_DEBUG
+void backend_set_log_level(int level);
+int backend_get_log_level(void);
+
 // Check connection status
 bool backend_is_wifi_connected(void);
 bool backend_is_mqtt_connected(void);
 
-// Main loop - call this regularly
+// Main loop - call this regularly from the LVGL thread; applies queued stdin
+// commands and emits mock water levels every interval_ms
 void backend_loop(void);
 
 #endif // BACKEND_H
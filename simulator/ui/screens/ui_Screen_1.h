@@ -31,10 +31,17 @@ extern lv_obj_t * ui_Label2;
 extern lv_obj_t * ui_Panel5;
 extern lv_obj_t * ui_Panel6;
 extern lv_obj_t * ui_Panel7;
+extern void ui_event_LightsTap(lv_event_t * e);
+extern void ui_event_ArcContainer(lv_event_t * e);
+// True while a finger is dragging the water level; async updates wait
+extern bool ui_Screen_1_water_dragging(void);
 extern void ui_event_RelaxSwitch(lv_event_t * e);
 extern lv_obj_t * ui_RelaxSwitch;
 extern void ui_event_BrightSwitch(lv_event_t * e);
 extern lv_obj_t * ui_BrightSwitch;
+extern lv_obj_t * ui_WifiIcon;
+extern lv_obj_t * ui_MqttDot;
+extern lv_obj_t * ui_WaterLiters;
 // CUSTOM VARIABLES
 extern lv_obj_t * uic_Screen_1;
 extern lv_obj_t * uic_ArcContainer;
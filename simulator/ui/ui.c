@@ -3,6 +3,8 @@
 // LVGL version: 8.3.11
 // Project name: SquareLine_Project
 
+#include <string.h>
+#include <stdatomic.h>
 #include "ui.h"
 #include "ui_helpers.h"
 #include "screens/ui_Screen_1.h"
@@ -26,7 +28,49 @@ lv_obj_t * ui____initial_actions0;
 
 ///////////////////// FUNCTIONS ////////////////////
 
-// Rust FFI declarations
+// Backend declarations
+#include "backend/backend.h"
+
+///////////////////// ASYNC UPDATES ////////////////////
+
+// Values posted from other threads (MQTT, backend), applied on the LVGL
+// thread by ui_async_timer_cb. -1 means nothing pending; only the latest
+// value is kept.
+#define UI_ASYNC_PERIOD_MS 50
+
+static atomic_int pending_water_level = -1;
+static atomic_int pending_bright_state = -1;
+static atomic_int pending_relax_state = -1;
+static atomic_bool pending_water_critical_toast = false;
+static atomic_int pending_mqtt_connected = -1;
+
+#define UI_TOAST_WATER_CRITICAL_MS 4000
+
+static void ui_async_timer_cb(lv_timer_t * timer)
+{
+    (void)timer;
+    int value;
+
+    // A level arriving mid-drag stays pending instead of fighting the
+    // finger; the drag's own value replaces it on release
+    if (!ui_Screen_1_water_dragging()) {
+        value = atomic_exchange(&pending_water_level, -1);
+        if (value >= 0) ui_set_water_level(value);
+    }
+
+    value = atomic_exchange(&pending_bright_state, -1);
+    if (value >= 0) ui_set_bright_state(value);
+
+    value = atomic_exchange(&pending_relax_state, -1);
+    if (value >= 0) ui_set_relax_state(value);
+
+    value = atomic_exchange(&pending_mqtt_connected, -1);
+    if (value >= 0) ui_set_mqtt_connected(value != 0);
+
+    if (atomic_exchange(&pending_water_critical_toast, false)) {
+        ui_show_toast("WATER CRITICAL", UI_TOAST_WATER_CRITICAL_MS);
+    }
+}
 
 ///////////////////// SCREENS ////////////////////
 
@@ -39,6 +83,7 @@ void ui_init(void)
     ui_Screen_1_screen_init();
     ui____initial_actions0 = lv_obj_create(NULL);
     lv_disp_load_scr(ui_Screen_1);
+    lv_timer_create(ui_async_timer_cb, UI_ASYNC_PERIOD_MS, NULL);
 }
 
 void ui_destroy(void)
@@ -51,17 +96,41 @@ void ui_destroy(void)
 
 void ui_update_water_level_async(int level)
 {
-    // This function is called from Rust threads (non-LVGL thread)
-    // Use lv_async_call to safely update UI from main thread
-    printf("[UI] Async water level update received: %d%%\n", level);
-    ui_set_water_level(level);
+    // Safe from any thread; applied on the LVGL thread within UI_ASYNC_PERIOD_MS
+    if (level < 0) level = 0;
+    if (level > 100) level = 100;
+    UI_LOG(BACKEND_LOG_DEBUG, "Async water level update received: %d%%", level);
+    atomic_store(&pending_water_level, level);
+}
+
+void ui_set_bright_state_async(int state)
+{
+    atomic_store(&pending_bright_state, state ? 1 : 0);
+}
+
+void ui_set_relax_state_async(int state)
+{
+    atomic_store(&pending_relax_state, state ? 1 : 0);
+}
+
+void ui_show_water_critical_async(void)
+{
+    atomic_store(&pending_water_critical_toast, true);
+}
+
+void ui_set_mqtt_connected_async(bool connected)
+{
+    atomic_store(&pending_mqtt_connected, connected ? 1 : 0);
 }
 
 void ui_set_water_level(int level)
 {
     // This function should be called from LVGL thread only
     // Updates the water level display
-    printf("[UI] Updating water level display: %d%%\n", level);
+    // Not logged per frame while dragging
+    if (!ui_Screen_1_water_dragging()) {
+        UI_LOG(BACKEND_LOG_DEBUG, "Updating water level display: %d%%", level);
+    }
     
     // Clamp level to 0-100
     if (level < 0) level = 0;
@@ -79,17 +148,35 @@ void ui_set_water_level(int level)
         lv_label_set_text(ui_WaterLevel, buf);
     }
     
+    // Approximate volume, only when the tank size is known
+    if (ui_WaterLiters != NULL) {
+        unsigned capacity = backend_get_tank_capacity();
+        if (capacity > 0) {
+            unsigned deciliters = (unsigned)level * capacity / 10;
+            lv_label_set_text_fmt(ui_WaterLiters, "%u.%u L", deciliters / 10, deciliters % 10);
+            lv_obj_clear_flag(ui_WaterLiters, LV_OBJ_FLAG_HIDDEN);
+        } else {
+            lv_obj_add_flag(ui_WaterLiters, LV_OBJ_FLAG_HIDDEN);
+        }
+    }
+    
     // Change arc color based on level
     if (ui_WaterTankArc != NULL) {
-        if (level < 10) {
-            // Critical - red
-            lv_obj_set_style_arc_color(ui_WaterTankArc, lv_color_hex(0xFF0000), LV_PART_INDICATOR | LV_STATE_DEFAULT);
-        } else if (level < 20) {
-            // Low - orange
-            lv_obj_set_style_arc_color(ui_WaterTankArc, lv_color_hex(0xFFA500), LV_PART_INDICATOR | LV_STATE_DEFAULT);
-        } else {
-            // Normal - blue
-            lv_obj_set_style_arc_color(ui_WaterTankArc, lv_color_hex(0x1F84D8), LV_PART_INDICATOR | LV_STATE_DEFAULT);
+        // The backend band has hysteresis, so a level hovering on a
+        // threshold doesn't flicker between colors
+        switch (backend_get_water_band()) {
+            case WATER_BAND_CRITICAL:
+                // Critical - red
+                lv_obj_set_style_arc_color(ui_WaterTankArc, lv_color_hex(0xFF0000), LV_PART_INDICATOR | LV_STATE_DEFAULT);
+                break;
+            case WATER_BAND_LOW:
+                // Low - orange
+                lv_obj_set_style_arc_color(ui_WaterTankArc, lv_color_hex(0xFFA500), LV_PART_INDICATOR | LV_STATE_DEFAULT);
+                break;
+            default:
+                // Normal - blue
+                lv_obj_set_style_arc_color(ui_WaterTankArc, lv_color_hex(0x1F84D8), LV_PART_INDICATOR | LV_STATE_DEFAULT);
+                break;
         }
     }
 }
@@ -97,7 +184,7 @@ void ui_set_water_level(int level)
 void ui_set_bright_state(int state)
 {
     // Updates bright switch state from Rust/backend
-    printf("[UI] Setting bright state: %d\n", state);
+    UI_LOG(BACKEND_LOG_DEBUG, "Setting bright state: %d", state);
     if (ui_BrightSwitch != NULL) {
         if (state) {
             lv_obj_add_state(ui_BrightSwitch, LV_STATE_CHECKED);
@@ -110,7 +197,7 @@ void ui_set_bright_state(int state)
 void ui_set_relax_state(int state)
 {
     // Updates relax switch state from Rust/backend
-    printf("[UI] Setting relax state: %d\n", state);
+    UI_LOG(BACKEND_LOG_DEBUG, "Setting relax state: %d", state);
     if (ui_RelaxSwitch != NULL) {
         if (state) {
             lv_obj_add_state(ui_RelaxSwitch, LV_STATE_CHECKED);
@@ -119,3 +206,144 @@ void ui_set_relax_state(int state)
         }
     }
 }
+
+void ui_set_wifi_connected(bool connected)
+{
+    if (ui_WifiIcon == NULL) return;
+
+    // Bars when connected, a red X when the network is down
+    lv_label_set_text(ui_WifiIcon, connected ? LV_SYMBOL_WIFI : LV_SYMBOL_CLOSE);
+    lv_obj_set_style_text_color(ui_WifiIcon, connected ? lv_color_hex(0xFFFFFF) : lv_color_hex(0xFF0000),
+                                LV_PART_MAIN | LV_STATE_DEFAULT);
+}
+
+void ui_set_wifi_rssi(int rssi)
+{
+    if (ui_WifiIcon == NULL) return;
+
+    // Tint the connected icon by signal quality: good, fair, weak
+    lv_color_t color = lv_color_hex(0xFFFFFF);
+    if (rssi < -80) {
+        color = lv_color_hex(0xFFA500);
+    } else if (rssi < -67) {
+        color = lv_color_hex(0xFFF526);
+    }
+    lv_obj_set_style_text_color(ui_WifiIcon, color, LV_PART_MAIN | LV_STATE_DEFAULT);
+}
+
+void ui_set_mqtt_connected(bool connected)
+{
+    if (ui_MqttDot != NULL) {
+        lv_obj_set_style_bg_color(ui_MqttDot, connected ? lv_color_hex(0x00C853) : lv_color_hex(0xFF0000),
+                                  LV_PART_MAIN | LV_STATE_DEFAULT);
+    }
+
+    // Dim the water percentage while it may be stale
+    if (ui_WaterLevel != NULL) {
+        lv_obj_set_style_text_opa(ui_WaterLevel, connected ? LV_OPA_COVER : LV_OPA_40,
+                                  LV_PART_MAIN | LV_STATE_DEFAULT);
+    }
+}
+
+///////////////////// TOAST ////////////////////
+
+static lv_obj_t * toast_obj = NULL;
+static lv_obj_t * toast_label = NULL;
+static lv_timer_t * toast_timer = NULL;
+
+static void ui_toast_dismiss_cb(lv_timer_t * timer)
+{
+    (void)timer;
+    // Deleting the banner invalidates its area, so the screen below repaints
+    if (toast_obj != NULL) {
+        lv_obj_del(toast_obj);
+        toast_obj = NULL;
+        toast_label = NULL;
+    }
+    toast_timer = NULL;
+}
+
+void ui_show_toast(const char * text, uint32_t duration_ms)
+{
+    if (text == NULL) return;
+
+    // Top layer keeps the banner above whatever screen is loaded
+    if (toast_obj == NULL) {
+        toast_obj = lv_obj_create(lv_layer_top());
+        lv_obj_remove_style_all(toast_obj);
+        lv_obj_set_size(toast_obj, 360, LV_SIZE_CONTENT);
+        lv_obj_center(toast_obj);
+        lv_obj_clear_flag(toast_obj, LV_OBJ_FLAG_CLICKABLE | LV_OBJ_FLAG_SCROLLABLE);
+        lv_obj_set_style_radius(toast_obj, 12, LV_PART_MAIN | LV_STATE_DEFAULT);
+        lv_obj_set_style_bg_color(toast_obj, lv_color_hex(0xB00020), LV_PART_MAIN | LV_STATE_DEFAULT);
+        lv_obj_set_style_bg_opa(toast_obj, LV_OPA_90, LV_PART_MAIN | LV_STATE_DEFAULT);
+        lv_obj_set_style_pad_all(toast_obj, 16, LV_PART_MAIN | LV_STATE_DEFAULT);
+
+        toast_label = lv_label_create(toast_obj);
+        lv_obj_set_width(toast_label, lv_pct(100));
+        lv_obj_set_style_text_align(toast_label, LV_TEXT_ALIGN_CENTER, LV_PART_MAIN | LV_STATE_DEFAULT);
+        lv_obj_set_style_text_color(toast_label, lv_color_hex(0xFFFFFF), LV_PART_MAIN | LV_STATE_DEFAULT);
+        lv_obj_set_style_text_font(toast_label, &lv_font_montserrat_24, LV_PART_MAIN | LV_STATE_DEFAULT);
+    }
+    lv_label_set_text(toast_label, text);
+
+    // A new toast replaces the current one and restarts the countdown
+    if (toast_timer != NULL) {
+        lv_timer_del(toast_timer);
+    }
+    toast_timer = lv_timer_create(ui_toast_dismiss_cb, duration_ms, NULL);
+    lv_timer_set_repeat_count(toast_timer, 1);
+}
+
+void ui_water_long_press(lv_coord_t x, lv_coord_t y)
+{
+    // "Mark full" after refilling the tank; the next real reading overrides it
+    (void)x;
+    (void)y;
+    backend_water_mark_full();
+}
+
+void ui_set_background_gradient(uint32_t top_hex, uint32_t bottom_hex)
+{
+    if (ui_Screen_1 == NULL) return;
+
+    // lv_color_hex converts the 24-bit value to the display's RGB565
+    lv_obj_set_style_bg_color(ui_Screen_1, lv_color_hex(top_hex), LV_PART_MAIN | LV_STATE_DEFAULT);
+    lv_obj_set_style_bg_grad_color(ui_Screen_1, lv_color_hex(bottom_hex), LV_PART_MAIN | LV_STATE_DEFAULT);
+    lv_obj_set_style_bg_grad_dir(ui_Screen_1, top_hex == bottom_hex ? LV_GRAD_DIR_NONE : LV_GRAD_DIR_VER,
+                                 LV_PART_MAIN | LV_STATE_DEFAULT);
+}
+
+static void ui_bitmap_delete_cb(lv_event_t * e)
+{
+    // Free the descriptor allocated in ui_bitmap_create
+    lv_img_dsc_t * dsc = lv_event_get_user_data(e);
+    lv_mem_free(dsc);
+}
+
+lv_obj_t * ui_bitmap_create(lv_obj_t * parent, const uint16_t * data, uint16_t w, uint16_t h, bool chroma_keyed)
+{
+    if (data == NULL || w == 0 || h == 0) {
+        return NULL;
+    }
+    
+    lv_img_dsc_t * dsc = lv_mem_alloc(sizeof(lv_img_dsc_t));
+    if (dsc == NULL) {
+        return NULL;
+    }
+    
+    memset(dsc, 0, sizeof(lv_img_dsc_t));
+    dsc->header.always_zero = 0;
+    dsc->header.w = w;
+    dsc->header.h = h;
+    dsc->header.cf = chroma_keyed ? LV_IMG_CF_TRUE_COLOR_CHROMA_KEYED : LV_IMG_CF_TRUE_COLOR;
+    dsc->data_size = (uint32_t)w * h * sizeof(uint16_t);
+    dsc->data = (const uint8_t *)data;
+    
+    // LVGL clips the image against its parent and the display, so icons
+    // placed partially off-screen are drawn safely
+    lv_obj_t * img = lv_img_create(parent);
+    lv_img_set_src(img, dsc);
+    lv_obj_add_event_cb(img, ui_bitmap_delete_cb, LV_EVENT_DELETE, dsc);
+    return img;
+}
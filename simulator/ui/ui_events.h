@@ -10,6 +10,10 @@
 extern "C" {
 #endif
 
+// Long-press on the water panel (held > UI_LONG_PRESS_TIME_MS without moving):
+// marks the tank full
+void ui_water_long_press(lv_coord_t x, lv_coord_t y);
+
 #ifdef __cplusplus
 } /*extern "C"*/
 #endif